@@ -24,6 +24,8 @@ pub enum Action {
     EmmitEvent(crossterm::event::Event),
     /// Emmit key inputs with modifiers. Each key will be processed as `press`ed and `release`d
     KeyInputs(Vec<(KeyCode, KeyModifiers)>),
+    /// Copy text to the clipboard, see [`App::copy_to_clipboard`]
+    CopyToClipboard(String),
 
     /// Focus the next node
     FocusNext,
@@ -31,6 +33,9 @@ pub enum Action {
     FocusPrevious,
     /// Focus a specific node
     FocusNode(WeakNodeHandle),
+    /// Scroll the scrollable ancestors of a node so the node is visible, see
+    /// [`App::scroll_into_view`]
+    ScrollIntoView(NodeId),
 
     /// Recompute a node and it's children, then re-render the full tree using a minimal viewport.
     /// # Note
@@ -65,9 +70,11 @@ impl Action {
             Self::Resize(w, h) => format!("Resize({w}, {h})"),
             Self::EmmitEvent(e) => format!("EmmitEvent({e:?})"),
             Self::KeyInputs(k) => format!("KeyInputs({:?})", map_inputs(k)),
+            Self::CopyToClipboard(t) => format!("CopyToClipboard({} bytes)", t.len()),
             Self::FocusNext => "FocusNext".into(),
             Self::FocusPrevious => "FocusPrevious".into(),
             Self::FocusNode(n) => format!("FocusNode({})", node_id(n)),
+            Self::ScrollIntoView(id) => format!("ScrollIntoView({id:?})"),
             Self::RecomputeNode(n) => format!("RecomputeNode({})", node_id(n)),
             Self::RemoveNode(id) => format!("RemoveNode({id:?})"),
         }
@@ -130,6 +137,7 @@ impl ActionHandling for App {
             }
             Action::Resize(width, height) => {
                 self.canvas = Canvas::new(width as usize, height as usize);
+                self.context.selection = None;
                 self.viewport.resize(width, height);
                 self.hitmap.resize(width, height);
                 self.context.screen_size = (width, height);
@@ -145,21 +153,27 @@ impl ActionHandling for App {
                         kind: crossterm::event::KeyEventKind::Press,
                         state: crossterm::event::KeyEventState::NONE,
                     };
-                    if self.should_quit(&key_event) {
+                    // The remaining keys are dropped once the app quits
+                    let consumed = self.handle_quit_key(key_event);
+                    if self.should_quit {
                         return Ok(());
                     }
 
-                    self.dispatch_key_event(key_event);
+                    if !consumed {
+                        self.dispatch_key_event(key_event);
+                    }
                     key_event.kind = crossterm::event::KeyEventKind::Release;
                     self.dispatch_key_event(key_event);
                 }
             }
+            Action::CopyToClipboard(text) => self.copy_to_clipboard(&text)?,
             Action::FocusNext => {
                 if let Some((_, focus_weak)) = self.context.focus.clone() {
                     if let Some((new_focus_id, new_focus_weak)) =
                         cycle_focus_flat(focus_weak, None, Navigation::Next, true)
                     {
                         self.dispatch_node_focus_event(new_focus_id, new_focus_weak);
+                        self.context.emmit(Action::ScrollIntoView(new_focus_id));
                     }
                 }
             }
@@ -169,6 +183,7 @@ impl ActionHandling for App {
                         cycle_focus_flat(focus_weak, None, Navigation::Previous, true)
                     {
                         self.dispatch_node_focus_event(new_focus_id, new_focus_weak);
+                        self.context.emmit(Action::ScrollIntoView(new_focus_id));
                     }
                 }
             }
@@ -179,7 +194,9 @@ impl ActionHandling for App {
 
                 let node_id = node.borrow().id();
                 self.dispatch_node_focus_event(node_id, node_weak);
+                self.context.emmit(Action::ScrollIntoView(node_id));
             }
+            Action::ScrollIntoView(id) => self.scroll_into_view(id),
             Action::RecomputeNode(node_weak) => self.handle_recompute_node_action(node_weak),
             Action::RemoveNode(id) => {
                 if let Some(parent) = self.remove_node(id).map(|(parent, _)| parent) {
@@ -248,11 +265,18 @@ impl App {
 
     /// Handle [Action::RecomputeNode]
     fn handle_recompute_node_action(&mut self, node_weak: WeakNodeHandle) {
-        // True if the node is auto-sized, has changed size, or changed offset type.
+        // True if the node is auto-sized, flexible, a grid item, has changed size, or changed
+        // offset type.
         // Always false if the node is and was absolute, since that does not affect the
         // parent.
         let get_parent_predicate = |node: &Node| {
             let auto_sized = node.style.size.width.is_auto() || node.style.size.height.is_auto();
+            let flexible = node.style.is_flexible();
+            let grid_item = node
+                .parent
+                .as_ref()
+                .and_then(|p| p.upgrade())
+                .is_some_and(|p| p.try_borrow().is_ok_and(|p| p.style.grid.is_some()));
             let changed_size = node.cache().style.size != node.style.size;
             let changed_offset_type = !node.cache().style.offset.type_eq(node.style.offset);
 
@@ -264,8 +288,9 @@ impl App {
                 return false;
             }
 
-            // If the node is auto-sized, has changed size, or changed offset type
-            auto_sized || changed_size || changed_offset_type
+            // If the node is auto-sized, flexible, a grid item, has changed size, or changed offset
+            // type
+            auto_sized || flexible || grid_item || changed_size || changed_offset_type
         };
 
         let Some((_, node)) = get_parent_while(&node_weak, get_parent_predicate) else {
@@ -322,9 +347,18 @@ impl App {
             }
         }
 
+        drop(node);
+
+        // The selection is in screen coordinates, so it's cleared once the rows under it change
+        if let Some(selection) = self.context.selection
+            && *selection.rows().start() < cached_viewport.max.1
+            && *selection.rows().end() >= cached_viewport.min.1
+        {
+            self.set_selection(None);
+        }
+
         // Render the tree using the minimal viewport or the cached viewport which is the
         // parent's content size.
-        drop(node);
         self.render(cached_viewport);
     }
 }
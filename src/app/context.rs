@@ -1,4 +1,4 @@
-use crate::{Action, Event, NodeHandle, NodeId, WeakNodeHandle, action::Actions};
+use crate::{Action, Event, NodeHandle, NodeId, Selection, WeakNodeHandle, action::Actions};
 
 /// Used to store persistent context data for the application.
 #[derive(Debug, Default)]
@@ -20,6 +20,8 @@ pub struct AppContext {
     /// Current mouse position (in screen coords). It will be `None` if the app never
     /// received a mouse event.
     pub(crate) mouse_pos: Option<(u16, u16)>,
+    /// Current mouse text selection, see [`App::set_selection`](crate::App::set_selection)
+    pub(crate) selection: Option<Selection>,
 
     /// Actions queue for the application. Executed in the main loop.
     pub actions: Actions,
@@ -35,6 +37,7 @@ impl AppContext {
             hover: None,
             screen_size,
             mouse_pos: None,
+            selection: None,
             actions: Actions::new(),
         }
     }
@@ -68,6 +71,12 @@ impl AppContext {
     pub fn mouse_pos(&self) -> Option<(u16, u16)> {
         self.mouse_pos
     }
+
+    /// Current mouse text selection.
+    #[inline]
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection
+    }
 }
 
 /// Unlike [`AppContext`], this context is used to store temporary per-event per-node data. Passed
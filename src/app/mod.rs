@@ -3,12 +3,14 @@ mod context;
 mod event;
 pub mod focus;
 mod hitmap;
+mod selection;
 mod viewport;
 
 pub use action::Action;
 pub use context::{AppContext, Context};
 pub use event::Event;
 pub use hitmap::HitMap;
+pub use selection::Selection;
 pub use viewport::Viewport;
 
 use std::{
@@ -19,15 +21,28 @@ use std::{
 };
 
 use crossterm::{
-    event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind},
+    event::{
+        KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{LeaveAlternateScreen, disable_raw_mode},
 };
 
 use crate::*;
 
+/// Clipboard hook used by [App::copy_to_clipboard] when OSC 52 can't be used
+pub type ClipboardFallback = Box<dyn FnMut(&str)>;
+
 pub struct App {
     pub quit_on: Option<(KeyCode, KeyModifiers)>,
+    /// Maximum byte length of text copied with [Action::CopyToClipboard] through OSC 52, longer
+    /// texts are passed to `clipboard_fallback`. Terminals ignore or truncate large sequences.
+    pub clipboard_limit: usize,
+    /// Called with the copied text if it exceeds `clipboard_limit` or if OSC 52 can't be
+    /// written, e.g. to use a system clipboard library.
+    pub clipboard_fallback: Option<ClipboardFallback>,
+    /// Copy the mouse [Selection] to the clipboard when the mouse button is released
+    pub copy_on_select: bool,
     raw: bool,
     alternate: bool,
     root: NodeHandle,
@@ -91,6 +106,9 @@ impl App {
 
         App {
             quit_on: Some((KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            clipboard_limit: Self::CLIPBOARD_LIMIT,
+            clipboard_fallback: None,
+            copy_on_select: true,
             raw: true,
             alternate: true,
             root,
@@ -134,6 +152,9 @@ impl App {
         self.root
             .borrow()
             .render_to(viewport, &mut self.canvas, &mut self.hitmap);
+        if let Some(selection) = &self.context.selection {
+            self.canvas.highlight_selection(selection, &viewport);
+        }
         self.canvas.prune_redundant_codes();
 
         self.next_draw_with(viewport);
@@ -189,7 +210,7 @@ impl App {
     /// Handles an event, dispatching it to the target node if applicable.
     pub fn handle_crossterm_event(&mut self, event: crossterm::event::Event) -> io::Result<()> {
         if let Some(key_event) = event.as_key_event() {
-            if self.should_quit(&key_event) {
+            if self.handle_quit_key(key_event) {
                 return Ok(());
             }
         }
@@ -228,6 +249,55 @@ impl App {
         false
     }
 
+    /// Handles the `quit_on` key before it is dispatched. The focused node can handle it first,
+    /// like an [Input] copying its selection with `Ctrl+C`, otherwise the app quits.
+    /// Returns true if the key event was consumed.
+    pub(crate) fn handle_quit_key(&mut self, key_event: KeyEvent) -> bool {
+        let is_quit_key = self.quit_on.is_some_and(|(code, modifiers)| {
+            key_event.code == code && key_event.modifiers.contains(modifiers)
+        });
+
+        if is_quit_key
+            && !self.should_quit
+            && let Some((focus_id, _)) = self.context.focus
+            && self.dispatch_event(Event::Key(key_event), focus_id)
+        {
+            return true;
+        }
+
+        self.should_quit(&key_event)
+    }
+
+    /// Default `clipboard_limit`, about 100 KB once base64 encoded
+    pub const CLIPBOARD_LIMIT: usize = 75_000;
+
+    /// Copies `text` to the clipboard with an OSC 52 sequence. The terminal sets its host's
+    /// clipboard, so it also works over SSH. Texts longer than `clipboard_limit` or failed writes
+    /// are passed to `clipboard_fallback`.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> io::Result<()> {
+        use crossterm::clipboard::CopyToClipboard;
+
+        if text.len() <= self.clipboard_limit {
+            match execute!(io::stdout(), CopyToClipboard::to_clipboard_from(text)) {
+                Ok(()) => return Ok(()),
+                Err(err) if self.clipboard_fallback.is_none() => return Err(err),
+                Err(_) => {}
+            }
+        }
+
+        if let Some(fallback) = &mut self.clipboard_fallback {
+            fallback(text);
+        } else {
+            warn!(
+                "Clipboard text of {} bytes exceeds the limit of {} bytes",
+                text.len(),
+                self.clipboard_limit
+            );
+        }
+
+        Ok(())
+    }
+
     /// Runs the main application loop.
     pub fn run(&mut self) -> io::Result<()> {
         self.prepare_screen()?;
@@ -272,7 +342,6 @@ impl App {
         }
     }
 
-
     /// Find a node by its [`id`](NodeId), returns its `weak handle` if found in the tree.
     /// The weak handle is guaranteed to be valid when returned
     pub fn get_weak_by_id(&self, id: NodeId) -> Option<WeakNodeHandle> {
@@ -309,13 +378,55 @@ impl App {
         self.dispatch_event(Event::Paste(paste), focus_id);
     }
 
-    /// Dispatches a key event to the target node based on current focus.
+    /// Dispatches a key event to the target node based on current focus. If it's not handled,
+    /// scrolling keys scroll the nearest scrollable ancestor of the focused node. This fallback
+    /// only applies if the focused node is scrollable itself or has no event handlers, so keys
+    /// left unhandled by elements like inputs don't scroll the page.
     pub fn dispatch_key_event(&mut self, key_event: KeyEvent) {
         let Some((focus_id, _)) = self.context.focus else {
             return;
         };
 
-        self.dispatch_event(Event::Key(key_event), focus_id);
+        if self.dispatch_event(Event::Key(key_event), focus_id) {
+            return;
+        }
+
+        let is_press = !matches!(key_event.kind, KeyEventKind::Release);
+        if !is_press || !key_event.modifiers.is_empty() {
+            return;
+        }
+
+        let scroll_delta = |node: &Node| -> Option<(i32, i32)> {
+            let page_height = node.style.clamped_height().max(1) as i32;
+            let max = u16::MAX as i32;
+
+            let delta = match key_event.code {
+                KeyCode::Up => (0, -1),
+                KeyCode::Down => (0, 1),
+                KeyCode::Left => (-1, 0),
+                KeyCode::Right => (1, 0),
+                KeyCode::PageUp => (0, -page_height),
+                KeyCode::PageDown => (0, page_height),
+                KeyCode::Home => (0, -max),
+                KeyCode::End => (0, max),
+                _ => return None,
+            };
+            Some(delta)
+        };
+
+        let Some(path) = self.get_path_from(focus_id) else {
+            return;
+        };
+        if let Some((focused, _)) = path.first() {
+            let focused = focused.borrow();
+            let (overflow_x, overflow_y) = focused.style.overflow;
+            let is_scrollable = overflow_x.is_scrollable() || overflow_y.is_scrollable();
+            if !is_scrollable && focused.has_handlers() {
+                return;
+            }
+        }
+
+        self.scroll_nearest(&path, scroll_delta);
     }
 
     /// Dispatches a mouse event to the target node based on the hitmap.
@@ -329,8 +440,18 @@ impl App {
 
         // Handle hold, and replace target_id if dragging
         match mouse_event.kind {
-            MouseEventKind::Down(_) => self.context.hold = Some((column, row, target_id)),
-            MouseEventKind::Up(_) => self.context.hold = None,
+            MouseEventKind::Down(_) => {
+                self.context.hold = Some((column, row, target_id));
+                self.set_selection(None);
+            }
+            MouseEventKind::Up(_) => {
+                self.context.hold = None;
+                if self.copy_on_select
+                    && let Some(text) = self.selected_text()
+                {
+                    self.context.emmit(Action::CopyToClipboard(text));
+                }
+            }
             MouseEventKind::Drag(_) => {
                 if let Some((_, _, hold_target_id)) = self.context.hold {
                     target_id = hold_target_id;
@@ -339,7 +460,135 @@ impl App {
             _ => {}
         }
 
-        self.dispatch_event(Event::Mouse(mouse_event), target_id);
+        if self.dispatch_event(Event::Mouse(mouse_event), target_id) {
+            return;
+        }
+
+        // Select text by dragging, if not handled
+        if let MouseEventKind::Drag(MouseButton::Left) = mouse_event.kind {
+            if let Some((x, y, _)) = self.context.hold {
+                self.set_selection(Some(Selection::new((x, y), (column, row))));
+            }
+            return;
+        }
+
+        // Scroll the nearest scrollable node with the mouse wheel, if not handled
+        let step = Self::WHEEL_SCROLL_STEP;
+        let delta = match mouse_event.kind {
+            MouseEventKind::ScrollUp => (0, -step),
+            MouseEventKind::ScrollDown => (0, step),
+            MouseEventKind::ScrollLeft => (-step, 0),
+            MouseEventKind::ScrollRight => (step, 0),
+            _ => return,
+        };
+
+        if let Some(path) = self.get_path_from(target_id) {
+            self.scroll_nearest(&path, |_| Some(delta));
+        }
+    }
+
+    /// Replaces the mouse [Selection] and re-renders the rows of the old and the new selection.
+    /// The selection is in screen coordinates, it's cleared when a scroll or
+    /// [`Action::RecomputeNode`] re-renders any of its rows.
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        let old = std::mem::replace(&mut self.context.selection, selection);
+        if old == selection {
+            return;
+        }
+
+        let rows = old.iter().chain(selection.iter()).flat_map(|s| s.rows());
+        let (Some(min), Some(max)) = (rows.clone().min(), rows.max()) else {
+            return;
+        };
+
+        let mut viewport = self.viewport;
+        viewport.min = (0, min.min(viewport.screen.1));
+        viewport.max = (viewport.screen.0, (max + 1).min(viewport.screen.1));
+        self.render(viewport);
+    }
+
+    /// Returns the text of the mouse [Selection], `None` if nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self.context.selection.as_ref()?;
+        let text = self.canvas.selected_text(selection);
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// Number of cells scrolled by a single mouse wheel step
+    pub(crate) const WHEEL_SCROLL_STEP: i32 = 3;
+
+    /// Scrolls the first node in `path` which can be scrolled by the `(dx, dy)` delta returned
+    /// from `delta`, and recomputes it. Returns true if a node has been scrolled.
+    fn scroll_nearest(
+        &mut self,
+        path: &[(Rc<RefCell<Node>>, WeakNodeHandle)],
+        delta: impl Fn(&Node) -> Option<(i32, i32)>,
+    ) -> bool {
+        for (node, weak) in path {
+            let mut node = node.borrow_mut();
+            let Some((dx, dy)) = delta(&node) else {
+                return false;
+            };
+
+            if node.scroll_by(dx, dy) {
+                self.context.emmit(Action::RecomputeNode(weak.clone()));
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Scrolls the scrollable ancestors of the node `id` so the node is visible, starting with
+    /// the nearest one. If the node is larger than an ancestor's content box, its start is
+    /// aligned with the ancestor's content start.
+    pub fn scroll_into_view(&mut self, id: NodeId) {
+        /// Returns the delta which scrolls `start..start + size` into `view..view + view_size`
+        fn delta(start: i32, size: i32, view: i32, view_size: i32) -> i32 {
+            if start < view || size > view_size {
+                start - view
+            } else if start + size > view + view_size {
+                start + size - view - view_size
+            } else {
+                0
+            }
+        }
+
+        let Some(path) = self.get_path_from(id) else {
+            return;
+        };
+        let Some((target, _)) = path.first() else {
+            return;
+        };
+
+        let (mut x, mut y, width, height) = {
+            let target = target.borrow();
+            let (x, y) = target.absolute_position();
+            let (width, height) = target.style.total_size();
+            (x as i32, y as i32, width as i32, height as i32)
+        };
+
+        for (node, weak) in path.iter().skip(1) {
+            let mut node = node.borrow_mut();
+            if !node.style.is_scrollable() {
+                continue;
+            }
+
+            let (view_x, view_y) = node.content_position();
+            let view_width = node.style.clamped_width() as i32;
+            let view_height = node.style.clamped_height() as i32;
+
+            let dx = delta(x, width, view_x as i32, view_width);
+            let dy = delta(y, height, view_y as i32, view_height);
+
+            let old_scroll = node.scroll;
+            if node.scroll_by(dx, dy) {
+                // The target moves with the scrolled content
+                x -= node.scroll.0 as i32 - old_scroll.0 as i32;
+                y -= node.scroll.1 as i32 - old_scroll.1 as i32;
+                self.context.emmit(Action::RecomputeNode(weak.clone()));
+            }
+        }
     }
 
     /// Dispatches `lost` and `gained` events to the relevant nodes. Parents with
@@ -413,14 +662,15 @@ impl App {
         )
     }
 
-    /// Dispatches an event to the target node in capture, target and bubble phases
-    pub fn dispatch_event(&mut self, event: Event, target_id: NodeId) {
+    /// Dispatches an event to the target node in capture, target and bubble phases. Returns true
+    /// if a handler has stopped its propagation.
+    pub fn dispatch_event(&mut self, event: Event, target_id: NodeId) -> bool {
         let Some(path) = self.get_path_from(target_id) else {
-            return;
+            return false;
         };
 
         let Some((_, target_weak)) = path.first() else {
-            return;
+            return false;
         };
 
         // Set focus to target node on mouse down
@@ -438,11 +688,12 @@ impl App {
         }
 
         // Execute the event phases
-        self.execute_event_phases(event, &path);
+        self.execute_event_phases(event, &path)
     }
 
     /// Executes the event phases (capture, target, bubble) for the given event and target node.
-    /// You must provide a path for the execution.
+    /// You must provide a path for the execution. Returns true if a handler has stopped the
+    /// event's propagation.
     ///
     /// # Usage
     /// `path` must contain the target node as the **first element** and the root node as the
@@ -450,10 +701,14 @@ impl App {
     ///
     /// # Safety
     /// No borrows of nodes in the path should be held while calling this method.
-    fn execute_event_phases(&mut self, event: Event, path: &[(Rc<RefCell<Node>>, WeakNodeHandle)]) {
+    fn execute_event_phases(
+        &mut self,
+        event: Event,
+        path: &[(Rc<RefCell<Node>>, WeakNodeHandle)],
+    ) -> bool {
         let Some((target, target_weak)) = path.first() else {
             // No target node in the path, nothing to do
-            return;
+            return false;
         };
         let target_id = target.borrow().id();
 
@@ -471,7 +726,7 @@ impl App {
             let mut node = node.borrow_mut();
             context.self_weak = weak.clone();
             if node.handle_event(&mut context, true) {
-                return;
+                return true;
             }
         }
 
@@ -486,7 +741,7 @@ impl App {
             context.is_target_phase = false;
 
             if capture || bubble {
-                return;
+                return true;
             }
         }
 
@@ -495,9 +750,11 @@ impl App {
             let mut node = node.borrow_mut();
             context.self_weak = weak.clone();
             if node.handle_event(&mut context, false) {
-                return;
+                return true;
             }
         }
+
+        false
     }
 
     /// Removes a node from the tree by its `id`, returning the removed node's handle, and its old
@@ -647,3 +904,132 @@ impl DynamicTimeout {
         Duration::from_millis(dynamic_timeout_ms)
     }
 }
+
+#[cfg(test)]
+mod app_tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::node::test_utils::item;
+
+    /// Returns an app with a scrollable root and its focused `child`
+    fn app(child: NodeHandle) -> App {
+        let mut root = item(10, 5);
+        root.style.overflow = (Overflow::Auto, Overflow::Auto);
+        let root = root.into_handle();
+
+        let focus = (child.borrow().id(), child.weak());
+        root.add_child(child);
+        root.borrow_mut()
+            .compute(Offset::default(), Size::from_cells(100, 100));
+
+        let mut app = App::new(root);
+        app.context.focus = Some(focus);
+        app
+    }
+
+    fn down() -> KeyEvent {
+        KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn unhandled_keys_scroll_ancestor() {
+        let mut app = app(item(10, 20).into_handle());
+        app.dispatch_key_event(down());
+        assert_eq!(app.root.borrow().scroll, (0, 1));
+    }
+
+    #[test]
+    fn element_keys_dont_scroll_ancestor() {
+        let mut child = item(10, 20);
+        child.add_handler(|_: &mut Context, _: &mut Node| false, true);
+
+        let mut app = app(child.into_handle());
+        app.dispatch_key_event(down());
+        assert_eq!(app.root.borrow().scroll, (0, 0));
+    }
+
+    #[test]
+    fn scrollable_element_scrolls_itself() {
+        let mut child = item(10, 5);
+        child.style.overflow = (Overflow::Auto, Overflow::Auto);
+        child.add_handler(|_: &mut Context, _: &mut Node| false, true);
+        let child = child.into_handle();
+        child.add_child_node(item(10, 20));
+
+        let mut app = app(child);
+        app.dispatch_key_event(down());
+        assert_eq!(app.root.borrow().children[0].borrow().scroll, (0, 1));
+        assert_eq!(app.root.borrow().scroll, (0, 0));
+    }
+
+    #[test]
+    fn recompute_clears_overlapping_selection() {
+        let child = item(10, 2).into_handle();
+        let child_weak = child.weak();
+        let mut app = app(child);
+        app.context.emmit(Action::Resize(20, 10));
+        app.handle_actions().unwrap();
+
+        app.context.selection = Some(Selection::new((0, 6), (4, 7)));
+        app.context.emmit(Action::RecomputeNode(child_weak.clone()));
+        app.handle_actions().unwrap();
+        assert!(app.context.selection.is_some());
+
+        app.context.selection = Some(Selection::new((0, 1), (4, 7)));
+        app.context.emmit(Action::RecomputeNode(child_weak));
+        app.handle_actions().unwrap();
+        assert_eq!(app.context.selection, None);
+    }
+
+    #[test]
+    fn scroll_clears_selection() {
+        let mut app = app(item(10, 20).into_handle());
+        app.context.emmit(Action::Resize(20, 10));
+        app.handle_actions().unwrap();
+
+        app.context.selection = Some(Selection::new((0, 0), (4, 0)));
+        app.dispatch_key_event(down());
+        app.handle_actions().unwrap();
+        assert_eq!(app.root.borrow().scroll, (0, 1));
+        assert_eq!(app.context.selection, None);
+    }
+
+    #[test]
+    fn key_inputs_stop_at_quit() {
+        let keys = Rc::new(RefCell::new(Vec::new()));
+        let handles_quit = Rc::new(Cell::new(true));
+        let mut child = item(10, 1);
+        let (recorded, handles) = (Rc::clone(&keys), Rc::clone(&handles_quit));
+        child.add_handler(
+            move |c: &mut Context, _: &mut Node| {
+                let Some(key_event) = c.event.as_key_event() else {
+                    return false;
+                };
+                recorded.borrow_mut().push((key_event.code, key_event.kind));
+                key_event.code == KeyCode::Char('c') && handles.get()
+            },
+            true,
+        );
+        let mut app = app(child.into_handle());
+
+        let (c, x) = (KeyCode::Char('c'), KeyCode::Char('x'));
+        let inputs = vec![(c, KeyModifiers::CONTROL), (x, KeyModifiers::NONE)];
+        let press = |code| (code, KeyEventKind::Press);
+        let release = |code| (code, KeyEventKind::Release);
+
+        // The handled quit key is pressed and released like the other keys
+        app.context.emmit(Action::KeyInputs(inputs.clone()));
+        app.handle_actions().unwrap();
+        assert!(!app.should_quit);
+        assert_eq!(*keys.borrow(), [press(c), release(c), press(x), release(x)]);
+
+        // The keys after the unhandled quit key are dropped
+        keys.borrow_mut().clear();
+        handles_quit.set(false);
+        app.context.emmit(Action::KeyInputs(inputs));
+        app.handle_actions().unwrap();
+        assert!(app.should_quit);
+        assert_eq!(*keys.borrow(), [press(c)]);
+    }
+}
@@ -0,0 +1,74 @@
+use std::ops::{Range, RangeInclusive};
+
+/// Text selection of the rendered screen, made by dragging the mouse over text which doesn't
+/// handle the drag itself. Like in a terminal, the rows between the first and the last row are
+/// selected fully, so a selection can span multiple nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Screen position `(column, row)` where the selection started
+    pub anchor: (u16, u16),
+    /// Screen position `(column, row)` where the selection ends, it's included in the selection
+    pub head: (u16, u16),
+}
+
+impl Selection {
+    /// Creates a new selection from `anchor` to `head`
+    pub fn new(anchor: (u16, u16), head: (u16, u16)) -> Self {
+        Self { anchor, head }
+    }
+
+    /// Returns the `(start, end)` positions ordered by row and column
+    pub fn ordered(&self) -> ((u16, u16), (u16, u16)) {
+        let key = |(column, row): (u16, u16)| (row, column);
+
+        if key(self.anchor) <= key(self.head) {
+            (self.anchor, self.head)
+        } else {
+            (self.head, self.anchor)
+        }
+    }
+
+    /// Returns the range of selected rows
+    pub fn rows(&self) -> RangeInclusive<u16> {
+        let (start, end) = self.ordered();
+        start.1..=end.1
+    }
+
+    /// Returns the selected columns on `row`, for a screen `width` columns wide. `None` if the
+    /// row is not selected.
+    pub fn columns(&self, row: u16, width: u16) -> Option<Range<usize>> {
+        let (start, end) = self.ordered();
+        if !self.rows().contains(&row) {
+            return None;
+        }
+
+        let from = if row == start.1 { start.0 } else { 0 };
+        let to = if row == end.1 { end.0 + 1 } else { width };
+        let to = to.min(width);
+
+        (from < to).then_some(from as usize..to as usize)
+    }
+}
+
+#[cfg(test)]
+mod selection_tests {
+    use super::*;
+
+    #[test]
+    fn single_row() {
+        let selection = Selection::new((5, 2), (1, 2));
+        assert_eq!(selection.ordered(), ((1, 2), (5, 2)));
+        assert_eq!(selection.columns(2, 10), Some(1..6));
+        assert_eq!(selection.columns(1, 10), None);
+    }
+
+    #[test]
+    fn multiple_rows() {
+        let selection = Selection::new((3, 4), (6, 1));
+        assert_eq!(selection.rows(), 1..=4);
+        assert_eq!(selection.columns(1, 8), Some(6..8));
+        assert_eq!(selection.columns(2, 8), Some(0..8));
+        assert_eq!(selection.columns(4, 8), Some(0..4));
+        assert_eq!(selection.columns(1, 5), None);
+    }
+}
@@ -2,11 +2,11 @@ use std::io::{self, Write, stdout};
 
 use crossterm::{
     QueueableCommand, cursor, queue,
-    style::{self, Color},
+    style::{self, Attribute, Color},
 };
 
 use crate::{
-    Code, Line, Size, Style, Viewport,
+    Code, Line, Selection, Style, Viewport,
     text::{StyledUnit, Text},
 };
 
@@ -68,24 +68,54 @@ impl Canvas {
         }
     }
 
-    /// Add wrapped text
-    pub fn add_text(&mut self, text: &Text, size: Size) {
-        let width = size.width.computed_size() as usize;
-        let height = size.height.computed_size() as usize;
+    /// Add wrapped text aligned with the [`text_align`](Style::text_align) style, and truncated
+    /// with the [`text_overflow`](Style::text_overflow) and [`max_lines`](Style::max_lines)
+    /// styles, scrolled by `scroll` columns and lines. Returns the height of the text, including
+    /// the lines scrolled out of view
+    pub fn add_text(&mut self, text: &Text, style: &Style, scroll: (u16, u16)) -> usize {
+        let width = style.size.width.computed_size() as usize;
+        let height = style.size.height.computed_size() as usize;
 
         if width == 0 || height == 0 {
-            return;
+            return 0;
         }
 
-        for line in &text.visual {
-            // let line = line.cutout(0, width);
-            let line = Line {
+        let max_lines = style.max_lines.map_or(usize::MAX, |max| max as usize);
+        let lines = &text.visual[..text.visual.len().min(max_lines)];
+        let is_clamped = lines.len() < text.visual.len();
+        let overflow = style.text_overflow;
+
+        for (i, line) in lines
+            .iter()
+            .enumerate()
+            .skip(scroll.1 as usize)
+            .take(height)
+        {
+            let mut line = Line {
                 content: line.content.clone(),
             };
-            // if self.buffer.len() < height {
+
+            // The last line of a paragraph is followed by an unwrapped line
+            let last = text.visual.get(i + 1).is_none_or(|l| l.offset == 0);
+            line.align_to(width, style.text_align, last);
+
+            // Truncate unwrapped lines, keeping the codes balanced. Scrolled lines are clipped
+            if scroll.0 > 0 {
+                line = line.cutout(scroll.0 as usize, width);
+            } else if line.width() > width {
+                line = line.truncate(width, overflow);
+            }
+
+            // Mark the clamped text at the end of its last line, the hidden lines follow it no
+            // matter the ellipsis position of the overflow mode
+            if is_clamped && overflow.is_ellipsis() && i + 1 == lines.len() {
+                line = line.ellipsize_end(width);
+            }
+
             self.buffer.push(line);
-            // }
         }
+
+        lines.len()
     }
 
     pub fn add_bg(&mut self, color: Option<Color>) {
@@ -368,6 +398,91 @@ impl Canvas {
         self.buffer[y as usize].paste_on_top(&line, column);
     }
 
+    /// Highlights the `selection` with reverse video within the `viewport`. Wide graphemes are
+    /// highlighted fully if any of their columns is selected.
+    pub fn highlight_selection(&mut self, selection: &Selection, viewport: &Viewport) {
+        let reverse = StyledUnit::Code(Code::Attribute(Attribute::Reverse));
+        let rows = *selection.rows().start().max(&viewport.min.1)
+            ..(*selection.rows().end() + 1).min(viewport.max.1);
+
+        for row in rows {
+            let y = row as i16 - self.position.1;
+            let Some(line) = usize::try_from(y).ok().and_then(|y| self.buffer.get_mut(y)) else {
+                continue;
+            };
+
+            let width = line.width();
+            let Some(columns) = selection.columns(row, width as u16) else {
+                continue;
+            };
+            let start = columns.start.max(viewport.min.0 as usize);
+            let end = columns.end.min(viewport.max.0 as usize);
+            if start >= end {
+                continue;
+            }
+
+            // Extend the range to grapheme boundaries
+            let (_, start, _) = line.column_to_index(start);
+            let (_, last, last_width) = line.column_to_index(end - 1);
+            let end = last + last_width;
+
+            // Reverse has to be reapplied after codes which reset it
+            let mut segment = Line::new(0);
+            segment.content.push(reverse.clone());
+            for unit in line.cutout(start, end - start).content {
+                let resets = matches!(
+                    unit,
+                    StyledUnit::Code(Code::Attribute(Attribute::Reset | Attribute::NoReverse))
+                );
+
+                segment.content.push(unit);
+                if resets {
+                    segment.content.push(reverse.clone());
+                }
+            }
+            segment
+                .content
+                .push(StyledUnit::Code(Code::Attribute(Attribute::NoReverse)));
+
+            line.paste_on_top(&segment, start);
+        }
+    }
+
+    /// Returns the text of the `selection`. Wide graphemes are included if any of their columns
+    /// is selected, trailing whitespace is removed from each row.
+    pub fn selected_text(&self, selection: &Selection) -> String {
+        let mut rows = Vec::new();
+
+        for row in selection.rows() {
+            let y = row as i16 - self.position.1;
+            let Some(line) = usize::try_from(y).ok().and_then(|y| self.buffer.get(y)) else {
+                continue;
+            };
+
+            let Some(columns) = selection.columns(row, line.width() as u16) else {
+                rows.push(String::new());
+                continue;
+            };
+
+            let mut text = String::new();
+            let mut column = 0;
+            for unit in &line.content {
+                let StyledUnit::Grapheme(grapheme) = unit else {
+                    continue;
+                };
+
+                if column < columns.end && column + grapheme.width > columns.start {
+                    text.push_str(&grapheme.str);
+                }
+                column += grapheme.width;
+            }
+
+            rows.push(text.trim_end().to_string());
+        }
+
+        rows.join("\n")
+    }
+
     /// Prune redundant codes from the canvas, removing any codes that are not needed and have no
     /// effect, like duplicates.
     pub fn prune_redundant_codes(&mut self) {
@@ -380,6 +495,7 @@ impl Canvas {
 #[cfg(test)]
 mod canvas {
     use super::*;
+    use crate::{Size, TextOverflow};
 
     fn canvas(w: usize, h: usize) -> Canvas {
         Canvas::new(w, h)
@@ -491,4 +607,57 @@ mod canvas {
         assert_eq!(canvas.buffer[0].content.len(), 5 + 2);
         assert_eq!(canvas.buffer[0].count(), 5);
     }
+
+    #[test]
+    fn selected_text_wide_graphemes() {
+        let mut canvas = canvas(6, 3);
+        canvas.buffer[0] = Line::from_string("ab漢cd");
+        canvas.buffer[1] = Line::from_string("ef    ");
+        canvas.buffer[2] = Line::from_string("漢字ij");
+
+        let selection = Selection::new((3, 0), (2, 2));
+        assert_eq!(canvas.selected_text(&selection), "漢cd\nef\n漢字");
+    }
+
+    #[test]
+    fn highlight_selection() {
+        let mut canvas = canvas(6, 2);
+        canvas.buffer[0] = Line::from_string("ab漢cd");
+        canvas.buffer[1] = Line::from_string("efghij");
+
+        let selection = Selection::new((3, 0), (0, 1));
+        canvas.highlight_selection(&selection, &Viewport::new(6, 2));
+        canvas.prune_redundant_codes();
+
+        let reverse = StyledUnit::Code(Code::Attribute(Attribute::Reverse));
+        let reset = StyledUnit::Code(Code::Attribute(Attribute::Reset));
+        let first = &canvas.buffer[0];
+        assert_eq!(first.width(), 6);
+        assert_eq!(first.content[2], reverse);
+        assert_eq!(first.content.last(), Some(&reset));
+
+        let second = &canvas.buffer[1];
+        assert_eq!(second.width(), 6);
+        assert_eq!(second.content[0], reverse);
+        assert!(second.content[2].is_code());
+        assert!(second.content[3..].iter().all(|u| u.is_grapheme()));
+    }
+
+    #[test]
+    fn clamped_text_ellipsis_is_at_the_end() {
+        let mut text = Text::plain("one\ntwo\nthree");
+        text.prepare_lines(0..3);
+
+        let style = Style {
+            size: Size::from_cells(10, 3),
+            max_lines: Some(2),
+            text_overflow: TextOverflow::EllipsisStart,
+            ..Default::default()
+        };
+
+        let mut canvas = canvas(0, 0);
+        assert_eq!(canvas.add_text(&text, &style, (0, 0)), 2);
+        assert_eq!(canvas.buffer.len(), 2);
+        assert_eq!(canvas.buffer[1].to_string(), "two…");
+    }
 }
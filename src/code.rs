@@ -1,6 +1,10 @@
-use std::fmt::Display;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, LazyLock, RwLock},
+};
 
-use crossterm::style::{Attribute, Color, SetBackgroundColor, SetForegroundColor};
+use crossterm::style::{Attribute, Color, Colored, SetBackgroundColor, SetForegroundColor};
 
 use crate::text::Attrs;
 
@@ -9,6 +13,10 @@ pub enum Code {
     Attribute(Attribute),
     Background(Color),
     Foreground(Color),
+    /// Color of underlines, drawn with the underline [Attribute]s
+    UnderlineColor(Color),
+    /// OSC 8 hyperlink, `None` closes the active link
+    Hyperlink(Option<Link>),
 }
 
 impl Code {
@@ -18,6 +26,8 @@ impl Code {
             Self::Attribute(attr) => Self::Attribute(Attrs::get_reset_attr(*attr)),
             Self::Background(_) => Self::Background(Color::Reset),
             Self::Foreground(_) => Self::Foreground(Color::Reset),
+            Self::UnderlineColor(_) => Self::UnderlineColor(Color::Reset),
+            Self::Hyperlink(_) => Self::Hyperlink(None),
         }
     }
 
@@ -27,6 +37,8 @@ impl Code {
             Self::Attribute(attr) => *attr == Attribute::Reset,
             Self::Background(color) => *color == Color::Reset,
             Self::Foreground(color) => *color == Color::Reset,
+            Self::UnderlineColor(color) => *color == Color::Reset,
+            Self::Hyperlink(link) => link.is_none(),
         }
     }
 
@@ -42,10 +54,63 @@ impl Display for Code {
             Code::Attribute(attr) => write!(f, "{}", attr),
             Code::Background(color) => write!(f, "{}", SetBackgroundColor(*color)),
             Code::Foreground(color) => write!(f, "{}", SetForegroundColor(*color)),
+            Code::UnderlineColor(color) => {
+                write!(f, "\x1b[{}m", Colored::UnderlineColor(*color))
+            }
+            Code::Hyperlink(Some(link)) => write!(f, "\x1b]8;id={};{}\x1b\\", link.0, link.url()),
+            Code::Hyperlink(None) => write!(f, "\x1b]8;;\x1b\\"),
         }
     }
 }
 
+/// Global hyperlink targets, indexed by [Link] ids
+static LINKS: LazyLock<RwLock<Links>> = LazyLock::new(RwLock::default);
+
+/// Interned hyperlink urls. Urls are kept for the lifetime of the program, since any copy of a
+/// [Link] can still refer to them.
+#[derive(Default)]
+struct Links {
+    urls: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, u32>,
+}
+
+/// Hyperlink target used by [Code::Hyperlink]. Urls are interned so the code can stay `Copy`,
+/// creating a link with the same url twice returns the same link.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Link(u32);
+
+impl Link {
+    /// Creates a new link to `url`, control characters are removed since they would terminate
+    /// the escape sequence.
+    pub fn new(url: &str) -> Self {
+        let url = url.chars().filter(|c| !c.is_control()).collect::<String>();
+
+        if let Ok(links) = LINKS.read()
+            && let Some(id) = links.ids.get(url.as_str())
+        {
+            return Self(*id);
+        }
+
+        let mut links = LINKS.write().unwrap_or_else(|e| e.into_inner());
+        // Another thread could have added the url between the locks
+        if let Some(id) = links.ids.get(url.as_str()) {
+            return Self(*id);
+        }
+
+        let url = Arc::<str>::from(url);
+        let id = links.urls.len() as u32;
+        links.urls.push(Arc::clone(&url));
+        links.ids.insert(url, id);
+        Self(id)
+    }
+
+    /// Returns the url of the link
+    pub fn url(&self) -> Arc<str> {
+        let links = LINKS.read().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(&links.urls[self.0 as usize])
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, Hash, PartialEq, Eq)]
 /// Represents a single code unit for one character in the terminal. Contains its foreground and
 /// background colors, as well as any attributes that apply to it.
@@ -54,8 +119,12 @@ pub struct CodeUnit {
     fg: Option<Color>,
     /// The background color of the code unit, `None` if not set or if `reset`.
     bg: Option<Color>,
+    /// The underline color of the code unit, `None` if not set or if `reset`.
+    underline: Option<Color>,
     /// The attributes of the code unit, `Attrs(0)` if not set or if `reset`.
     attrs: Attrs,
+    /// The hyperlink of the code unit, `None` if not set or if closed.
+    link: Option<Link>,
 }
 
 impl CodeUnit {
@@ -65,14 +134,20 @@ impl CodeUnit {
         Self {
             fg: None,
             bg: None,
+            underline: None,
             attrs: Attrs::default(),
+            link: None,
         }
     }
 
     /// Returns true if the code unit has no codes set.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.fg.is_none() && self.bg.is_none() && self.attrs.is_empty()
+        self.fg.is_none()
+            && self.bg.is_none()
+            && self.underline.is_none()
+            && self.attrs.is_empty()
+            && self.link.is_none()
     }
 
     /// Returns the foreground color.
@@ -107,6 +182,22 @@ impl CodeUnit {
         }
     }
 
+    /// Returns the underline color.
+    #[inline]
+    pub fn underline_color(&self) -> Option<Color> {
+        self.underline
+    }
+
+    /// Sets the underline color, `None` if `reset`.
+    #[inline]
+    pub fn set_underline_color(&mut self, color: Color) {
+        if color == Color::Reset {
+            self.underline = None;
+        } else {
+            self.underline = Some(color);
+        }
+    }
+
     /// Returns the attributes.
     #[inline]
     pub fn attrs(&self) -> Attrs {
@@ -125,6 +216,18 @@ impl CodeUnit {
         self.attrs = self.attrs.apply(attr);
     }
 
+    /// Returns the hyperlink.
+    #[inline]
+    pub fn link(&self) -> Option<Link> {
+        self.link
+    }
+
+    /// Sets the hyperlink, `None` if closed.
+    #[inline]
+    pub fn set_link(&mut self, link: Option<Link>) {
+        self.link = link;
+    }
+
     /// Returns the reset codes for this code unit.
     pub fn into_reset_codes(self) -> Vec<Code> {
         let mut codes = Vec::new();
@@ -137,10 +240,18 @@ impl CodeUnit {
             codes.push(Code::Background(Color::Reset));
         }
 
+        if self.underline.is_some() {
+            codes.push(Code::UnderlineColor(Color::Reset));
+        }
+
         if !self.attrs.is_empty() {
             codes.push(Code::Attribute(Attribute::Reset));
         }
 
+        if self.link.is_some() {
+            codes.push(Code::Hyperlink(None));
+        }
+
         codes
     }
 
@@ -156,10 +267,18 @@ impl CodeUnit {
             codes.push(Code::Background(bg));
         }
 
+        if let Some(underline) = self.underline {
+            codes.push(Code::UnderlineColor(underline));
+        }
+
         for attr in self.attrs {
             codes.push(Code::Attribute(attr));
         }
 
+        if let Some(link) = self.link {
+            codes.push(Code::Hyperlink(Some(link)));
+        }
+
         codes
     }
 }
@@ -0,0 +1,474 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    time::Duration,
+};
+
+use crossterm::style::{Attribute, Color};
+
+use crate::{
+    Action, Code, Context, Message, Node, Offset, Padding, Size, WeakNodeHandle,
+    text::{BufferLine, StyleSpan, Text, TextWrap},
+};
+
+pub type CompletionProvider = Box<dyn FnMut(&str, usize) -> Completions>;
+pub type AsyncCompletionProvider = Box<dyn FnMut(&str, usize) -> Completions + Send>;
+
+/// Source of completion candidates for an [Input](super::Input). The provider is called with the
+/// line containing the cursor and the grapheme index of the cursor in it.
+pub enum Completer {
+    /// Provider called on the main thread after every edit
+    Sync(CompletionProvider),
+    /// Provider called in a worker thread, for slow sources like file paths or remote names.
+    /// Requests made while it's busy are merged into the latest one.
+    Async(AsyncCompletionProvider),
+}
+
+impl Completer {
+    /// Creates a new [Completer::Sync] provider
+    pub fn new(f: impl FnMut(&str, usize) -> Completions + 'static) -> Self {
+        Self::Sync(Box::new(f))
+    }
+
+    /// Creates a new [Completer::Async] provider
+    pub fn new_async(f: impl FnMut(&str, usize) -> Completions + Send + 'static) -> Self {
+        Self::Async(Box::new(f))
+    }
+}
+
+/// Completion candidates returned by a [Completer]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Completions {
+    /// Grapheme index in the line where the completed text starts, an accepted candidate replaces
+    /// the text from it to the cursor
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+impl Completions {
+    /// Creates new completions replacing the text from `start` with one of `candidates`
+    pub fn new(start: usize, candidates: Vec<String>) -> Self {
+        Self { start, candidates }
+    }
+}
+
+/// Completion request, the dropdown is placed under `cursor_position` `(column, row)` in the
+/// input node
+#[derive(Debug, Clone)]
+struct Request {
+    generation: u64,
+    line: String,
+    cursor: usize,
+    cursor_position: (u16, u16),
+}
+
+/// Provider as used by the input, async providers are moved to their worker
+enum Provider {
+    Sync(CompletionProvider),
+    Async(Sender<Request>),
+}
+
+/// Completion state of an [Input](super::Input), shows the candidates in a dropdown under the
+/// cursor
+pub(crate) struct Completion {
+    provider: Provider,
+    /// Candidates shown in the dropdown
+    shown: Option<Completions>,
+    /// Index of the selected candidate
+    selected: usize,
+    /// The last request
+    request: Option<Request>,
+    /// Absolutely positioned dropdown node, a child of the input node
+    dropdown: Option<WeakNodeHandle>,
+    /// Incremented with every request and dismissal, stale async results are dropped
+    generation: Arc<AtomicU64>,
+    /// Latest async result, taken on the next event
+    results: Arc<Mutex<Option<(u64, Completions)>>>,
+}
+
+impl Completion {
+    /// Name of the dropdown node
+    const DROPDOWN_NAME: &str = "completions";
+    /// Maximum number of candidates visible at once
+    const MAX_VISIBLE: usize = 8;
+    const BACKGROUND: Color = Color::Rgb {
+        r: 50,
+        g: 50,
+        b: 60,
+    };
+
+    /// Creates the completion state, async providers are started in a worker of `node`
+    pub(crate) fn new(completer: Completer, node: &mut Node) -> Self {
+        let generation = Arc::new(AtomicU64::new(0));
+        let results = Arc::new(Mutex::new(None));
+
+        let provider = match completer {
+            Completer::Sync(f) => Provider::Sync(f),
+            Completer::Async(f) => {
+                let (sender, receiver) = mpsc::channel();
+                Self::start_worker(f, receiver, &generation, &results, node);
+                Provider::Async(sender)
+            }
+        };
+
+        Self {
+            provider,
+            shown: None,
+            selected: 0,
+            request: None,
+            dropdown: None,
+            generation,
+            results,
+        }
+    }
+
+    /// Starts the worker completing the latest request from `receiver` with `f`
+    fn start_worker(
+        mut f: AsyncCompletionProvider,
+        receiver: Receiver<Request>,
+        generation: &Arc<AtomicU64>,
+        results: &Arc<Mutex<Option<(u64, Completions)>>>,
+        node: &mut Node,
+    ) {
+        let generation = Arc::clone(generation);
+        let results = Arc::clone(results);
+
+        node.start_worker(move |c| {
+            while !c.is_shutdown() {
+                let mut request = match receiver.recv_timeout(Duration::from_millis(100)) {
+                    Ok(request) => request,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                // Only the latest request is completed
+                while let Ok(newer) = receiver.try_recv() {
+                    request = newer;
+                }
+
+                let completions = f(&request.line, request.cursor);
+                if generation.load(Ordering::Relaxed) != request.generation {
+                    continue;
+                }
+                *results.lock().unwrap() = Some((request.generation, completions.clone()));
+
+                // Show the result right away, the input takes it on its next event
+                let generation = Arc::clone(&generation);
+                let message = Message::exec(move |c| {
+                    if generation.load(Ordering::Relaxed) != request.generation {
+                        return;
+                    }
+
+                    let node = c.node();
+                    let Some(dropdown) = node
+                        .children
+                        .iter()
+                        .find(|child| child.borrow().name == Self::DROPDOWN_NAME)
+                    else {
+                        return;
+                    };
+
+                    Self::render(&mut dropdown.borrow_mut(), &completions, 0, &request);
+                    c.app().emmit(Action::RecomputeNode(dropdown.weak()));
+                });
+                if c.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// True if the dropdown shows any candidates
+    pub(crate) fn is_open(&self) -> bool {
+        self.shown.is_some()
+    }
+
+    /// Returns the grapheme index where the completed text starts, and the selected candidate
+    pub(crate) fn selected(&self) -> Option<(usize, &str)> {
+        let shown = self.shown.as_ref()?;
+        let candidate = shown.candidates.get(self.selected)?;
+        Some((shown.start, candidate))
+    }
+
+    /// Takes the latest async result, unless it's stale
+    pub(crate) fn poll(&mut self) {
+        let Some((generation, completions)) = self.results.lock().unwrap().take() else {
+            return;
+        };
+
+        if generation == self.generation.load(Ordering::Relaxed) {
+            self.selected = 0;
+            self.shown = (!completions.candidates.is_empty()).then_some(completions);
+        }
+    }
+
+    /// Requests completions for the cursor at grapheme `cursor` in `line`. The dropdown is placed
+    /// under the `cursor_position` `(column, row)` in the input `node`.
+    pub(crate) fn request(
+        &mut self,
+        line: &str,
+        cursor: usize,
+        cursor_position: (u16, u16),
+        c: &mut Context,
+        node: &mut Node,
+    ) {
+        let request = Request {
+            generation: self.generation.fetch_add(1, Ordering::Relaxed) + 1,
+            line: line.to_string(),
+            cursor,
+            cursor_position,
+        };
+        self.create_dropdown(c, node);
+
+        match &mut self.provider {
+            Provider::Sync(f) => {
+                let completions = f(line, cursor);
+                self.selected = 0;
+                self.shown = (!completions.candidates.is_empty()).then_some(completions);
+                self.request = Some(request);
+                self.update(c);
+            }
+            Provider::Async(sender) => {
+                self.request = Some(request.clone());
+                if sender.send(request).is_err() {
+                    warn!("Completion worker of an input is not running");
+                }
+            }
+        }
+    }
+
+    /// Moves the selection by `dy` candidates, wrapping around
+    pub(crate) fn move_selection(&mut self, dy: isize, c: &mut Context) {
+        let Some(shown) = &self.shown else {
+            return;
+        };
+
+        let count = shown.candidates.len() as isize;
+        self.selected = (self.selected as isize + dy).rem_euclid(count) as usize;
+        self.update(c);
+    }
+
+    /// Hides the dropdown and drops pending async results
+    pub(crate) fn dismiss(&mut self, c: &mut Context) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.shown = None;
+        self.update(c);
+    }
+
+    /// Adds the dropdown node to the input `node` if it doesn't exist yet
+    fn create_dropdown(&mut self, c: &mut Context, node: &mut Node) {
+        if self
+            .dropdown
+            .as_ref()
+            .is_some_and(|d| d.upgrade().is_some())
+        {
+            return;
+        }
+
+        let mut dropdown = Node::default();
+        dropdown.name = Self::DROPDOWN_NAME.to_string();
+        dropdown.style.z_index = 10;
+        dropdown.style.bg = Some(Self::BACKGROUND);
+        dropdown.style.padding = Padding::new(0, 1);
+        Self::hide(&mut dropdown);
+
+        let dropdown = dropdown.into_handle();
+        self.dropdown = Some(dropdown.weak());
+        node.add_child(dropdown, c.self_weak.clone());
+    }
+
+    /// Renders the shown candidates to the dropdown, or hides it
+    fn update(&self, c: &mut Context) {
+        let Some(weak) = &self.dropdown else {
+            return;
+        };
+        let Some(dropdown) = weak.upgrade() else {
+            return;
+        };
+
+        let mut dropdown = dropdown.borrow_mut();
+        match (&self.shown, &self.request) {
+            (Some(shown), Some(request)) => {
+                Self::render(&mut dropdown, shown, self.selected, request)
+            }
+            _ => Self::hide(&mut dropdown),
+        }
+        c.app.emmit(Action::RecomputeNode(weak.clone()));
+    }
+
+    /// Shrinks the dropdown so it's not visible
+    fn hide(dropdown: &mut Node) {
+        dropdown.style.offset = Offset::AbsolutelyRelative(0, 0);
+        dropdown.style.size = Size::from_cells(0, 0);
+        dropdown.style.padding = Padding::new(0, 0);
+        dropdown.text = Text::default();
+    }
+
+    /// Renders `completions` to the dropdown under the completed text of `request`
+    fn render(dropdown: &mut Node, completions: &Completions, selected: usize, request: &Request) {
+        if completions.candidates.is_empty() {
+            return Self::hide(dropdown);
+        }
+
+        let lines = completions
+            .candidates
+            .iter()
+            .map(|candidate| BufferLine::new(candidate.clone()))
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or_default();
+        let height = lines.len().min(Self::MAX_VISIBLE);
+
+        // Align the candidates with the start of the completed text
+        let line = BufferLine::new(request.line.clone());
+        let completed_width: usize = line
+            .grapheme_data()
+            .iter()
+            .take(request.cursor)
+            .skip(completions.start)
+            .map(|(.., width)| width)
+            .sum();
+        let (column, row) = request.cursor_position;
+        // Account for the left padding
+        let x = column as i16 - completed_width as i16 - 1;
+        dropdown.style.offset = Offset::AbsolutelyRelative(x.max(0), row as i16 + 1);
+        dropdown.style.size = Size::from_cells(width as u16, height as u16);
+        dropdown.style.padding = Padding::new(0, 1);
+
+        let mut text = Text::new_from(lines);
+        text.wrap = TextWrap::None;
+        let length = text.input.get(selected).map_or(0, |l| l.count());
+        let code = Code::Attribute(Attribute::Reverse);
+        text.styles.push(StyleSpan::new(code, selected, 0, length));
+        text.prepare_lines(0..text.input.len());
+        dropdown.text = text;
+
+        // Keep the selected candidate visible
+        let top = dropdown.scroll.1 as usize;
+        if selected < top {
+            dropdown.scroll.1 = selected as u16;
+        } else if selected >= top + height {
+            dropdown.scroll.1 = (selected + 1 - height) as u16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod completion_tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    use super::*;
+    use crate::{
+        AppContext, Event, NodeHandle,
+        elements::{
+            Input, InputOptions,
+            test_utils::{NONE, dispatch, key, type_text},
+        },
+    };
+
+    /// Completes the word before the cursor with git commands
+    fn commands(line: &str, cursor: usize) -> Completions {
+        let start = line[..cursor].rfind(' ').map_or(0, |i| i + 1);
+        let word = &line[start..cursor];
+        let candidates = ["checkout", "cherry-pick", "commit"]
+            .into_iter()
+            .filter(|command| command.starts_with(word))
+            .map(str::to_string)
+            .collect();
+        Completions::new(start, candidates)
+    }
+
+    fn dropdown_lines(node: &NodeHandle) -> Vec<String> {
+        let node = node.borrow();
+        let dropdown = node.children[0].borrow();
+        dropdown
+            .text
+            .input
+            .iter()
+            .map(|l| l.content().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn accept_replaces_completed_text() {
+        let (node, value) = Input::with_options(
+            "",
+            InputOptions {
+                completer: Some(Completer::new(commands)),
+                ..Default::default()
+            },
+        );
+        let mut app = AppContext::new(&node, (80, 24));
+
+        type_text(&node, &mut app, "git ch");
+        assert_eq!(dropdown_lines(&node), ["checkout", "cherry-pick"]);
+
+        dispatch(&node, &mut app, key(KeyCode::Down, NONE));
+        dispatch(&node, &mut app, key(KeyCode::Tab, NONE));
+        assert_eq!(value.value(), "git cherry-pick");
+        assert!(dropdown_lines(&node).is_empty());
+
+        // A start after the cursor inserts the candidate at the cursor
+        let (node, value) = Input::with_options(
+            "",
+            InputOptions {
+                completer: Some(Completer::new(|_, _| {
+                    Completions::new(10, vec!["!".to_string()])
+                })),
+                ..Default::default()
+            },
+        );
+        let mut app = AppContext::new(&node, (80, 24));
+
+        type_text(&node, &mut app, "ab");
+        dispatch(&node, &mut app, key(KeyCode::Left, NONE));
+        dispatch(
+            &node,
+            &mut app,
+            key(KeyCode::Char(' '), KeyModifiers::CONTROL),
+        );
+        dispatch(&node, &mut app, key(KeyCode::Enter, NONE));
+        assert_eq!(value.value(), "a!b");
+    }
+
+    #[test]
+    fn stale_async_results_are_dropped() {
+        // Workers don't run outside of an app, the results are set like the worker would
+        let node = Node::default().into_handle();
+        let mut app = AppContext::new(&node, (80, 24));
+        let id = node.borrow().id();
+        let mut completion =
+            Completion::new(Completer::new_async(commands), &mut node.borrow_mut());
+
+        let result = |completion: &Completion, generation, candidate: &str| {
+            let completions = Completions::new(0, vec![candidate.to_string()]);
+            *completion.results.lock().unwrap() = Some((generation, completions));
+        };
+        let mut request = |completion: &mut Completion, dismiss: bool| {
+            let event = Event::TerminalFocusGained;
+            let mut c = Context::new(&mut app, id, event, node.weak(), node.weak());
+            if dismiss {
+                completion.dismiss(&mut c);
+            } else {
+                completion.request("c", 1, (0, 0), &mut c, &mut node.borrow_mut());
+            }
+        };
+
+        request(&mut completion, false);
+        result(&completion, 1, "checkout");
+        request(&mut completion, false);
+        completion.poll();
+        assert!(!completion.is_open());
+
+        result(&completion, 2, "commit");
+        completion.poll();
+        assert_eq!(completion.selected(), Some((0, "commit")));
+
+        // Results of requests before a dismissal are dropped too
+        request(&mut completion, true);
+        result(&completion, 2, "cherry-pick");
+        completion.poll();
+        assert!(!completion.is_open());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use super::{Console, Entry, HISTORY, push_history};
+use crate::AppContext;
+
+type CommandHandler =
+    Box<dyn FnMut(&mut AppContext, &[&str]) -> Result<String, String> + Send + 'static>;
+
+/// Command registered with [Console::register_command]
+struct Command {
+    help: String,
+    handler: Arc<Mutex<CommandHandler>>,
+}
+
+/// Global Console commands by name
+static COMMANDS: LazyLock<Mutex<BTreeMap<String, Command>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Splits a command `line` into arguments at whitespace. Single and double quotes group
+/// whitespace into one argument, a backslash escapes the next character outside of single quotes.
+fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = None::<String>;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(char) = chars.next() {
+        match (char, quote) {
+            ('\\', Some('\'')) => current.get_or_insert_default().push(char),
+            ('\\', _) => {
+                let escaped = chars.next().ok_or("Trailing backslash")?;
+                current.get_or_insert_default().push(escaped);
+            }
+            ('"' | '\'', None) => {
+                quote = Some(char);
+                current.get_or_insert_default();
+            }
+            (char, Some(q)) if char == q => quote = None,
+            (char, None) if char.is_whitespace() => args.extend(current.take()),
+            (char, _) => current.get_or_insert_default().push(char),
+        }
+    }
+
+    if let Some(quote) = quote {
+        return Err(format!("Unclosed quote {quote}"));
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+impl Console {
+    /// Name of the built-in command listing the registered commands
+    pub const HELP_COMMAND: &str = "help";
+
+    /// Registers a command executed by typing `name` and its arguments into the console input.
+    /// `help` is shown by the built-in `help` command. Registering an existing name replaces the
+    /// command.
+    ///
+    /// The `handler` gets the arguments split at whitespace, quotes group arguments with
+    /// whitespace. Its output is logged to the console history, an error is logged as an error.
+    pub fn register_command(
+        name: impl Into<String>,
+        help: impl Into<String>,
+        handler: impl FnMut(&mut AppContext, &[&str]) -> Result<String, String> + Send + 'static,
+    ) {
+        let command = Command {
+            help: help.into(),
+            handler: Arc::new(Mutex::new(Box::new(handler))),
+        };
+
+        if let Ok(mut commands) = COMMANDS.lock() {
+            commands.insert(name.into(), command);
+        }
+    }
+
+    /// Removes the command registered as `name`, returns true if it existed
+    pub fn unregister_command(name: &str) -> bool {
+        COMMANDS
+            .lock()
+            .is_ok_and(|mut commands| commands.remove(name).is_some())
+    }
+
+    /// Executes a command `line` as if it was typed into the console input, the line and the
+    /// command output are logged to the console history
+    pub fn execute(app: &mut AppContext, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        push_history(Entry::Info(format!("> {line}")));
+        // The history is not locked while logging, the logger can write to it
+        let saved = HISTORY.write().map(|mut history| history.push_input(line));
+        if let Ok(Err((_path, _err))) = saved {
+            warn!("Could not save console history to {_path:?}: {_err}");
+        }
+
+        let args = match split_args(line) {
+            Ok(args) => args,
+            Err(err) => return push_history(Entry::Error(err)),
+        };
+        let Some((name, args)) = args.split_first() else {
+            return;
+        };
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+        if name == Self::HELP_COMMAND {
+            return push_history(Self::help(&args));
+        }
+
+        // The registry is not locked while the handler runs, so it can register commands
+        let handler = COMMANDS
+            .lock()
+            .ok()
+            .and_then(|commands| commands.get(name).map(|c| Arc::clone(&c.handler)));
+        let Some(handler) = handler else {
+            return push_history(Entry::Error(format!(
+                "Unknown command `{name}`, type `{}` to list commands",
+                Self::HELP_COMMAND
+            )));
+        };
+
+        let Ok(mut handler) = handler.try_lock() else {
+            return push_history(Entry::Error(format!("Command `{name}` is already running")));
+        };
+        match handler(app, &args) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => push_history(Entry::Info(output)),
+            Err(err) => push_history(Entry::Error(err)),
+        }
+    }
+
+    /// Returns the help of the commands in `args`, or of all commands if it's empty
+    fn help(args: &[&str]) -> Entry {
+        let Ok(commands) = COMMANDS.lock() else {
+            return Entry::Error("Commands are not available".to_string());
+        };
+
+        if args.is_empty() {
+            let mut lines = vec![format!(
+                "{} [command] - Shows command help",
+                Self::HELP_COMMAND
+            )];
+            lines.extend(
+                commands
+                    .iter()
+                    .map(|(name, command)| format!("{name} - {}", command.help)),
+            );
+            return Entry::Info(lines.join("\n"));
+        }
+
+        let mut lines = Vec::new();
+        for name in args {
+            match commands.get(*name) {
+                Some(command) => lines.push(format!("{name} - {}", command.help)),
+                None => return Entry::Error(format!("Unknown command `{name}`")),
+            }
+        }
+        Entry::Info(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod command_tests {
+    use super::*;
+
+    #[test]
+    fn split_whitespace() {
+        let args = split_args("  set   flag  on ").unwrap();
+        assert_eq!(args, ["set", "flag", "on"]);
+    }
+
+    #[test]
+    fn split_quotes_and_escapes() {
+        let args = split_args(r#"echo "a b" 'c \d' e\ f """#).unwrap();
+        assert_eq!(args, ["echo", "a b", r"c \d", "e f", ""]);
+
+        assert!(split_args("echo \"open").is_err());
+        assert!(split_args("echo \\").is_err());
+    }
+}
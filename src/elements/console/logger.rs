@@ -1,45 +1,347 @@
-use log::{Level, LevelFilter, Metadata, Record};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-struct ConsoleLogger;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
 
-impl log::Log for ConsoleLogger {
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        #[cfg(debug_assertions)]
-        return metadata.level() <= Level::Trace;
+use super::Console;
+
+/// Logger writing [log] records to the [Console] history, created with
+/// [`ConsoleLogger::builder`]. Warnings and errors are logged as [`Console::warn`] and
+/// [`Console::error`] entries.
+pub struct ConsoleLogger {
+    /// Level of targets without a matching directive
+    default_level: LevelFilter,
+    /// Levels of target prefixes, sorted from the longest prefix
+    directives: Vec<(String, LevelFilter)>,
+    file: Option<Mutex<FileSink>>,
+    /// Logger which gets every record too
+    inner: Option<Box<dyn Log>>,
+}
+
+/// Builder of a [`ConsoleLogger`]
+pub struct ConsoleLoggerBuilder {
+    default_level: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+    file: Option<(PathBuf, u64, usize)>,
+    inner: Option<Box<dyn Log>>,
+}
+
+/// Log file which is rotated when it would exceed its maximum size. Rotated files have a numeric
+/// suffix, `app.log.1` is the newest one.
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl ConsoleLogger {
+    /// Returns a new builder, by default only records up to [`Level::Info`] are logged, or up to
+    /// [`Level::Trace`] in debug builds
+    pub fn builder() -> ConsoleLoggerBuilder {
+        let default_level = if cfg!(debug_assertions) {
+            LevelFilter::Trace
+        } else {
+            LevelFilter::Info
+        };
+
+        ConsoleLoggerBuilder {
+            default_level,
+            directives: Vec::new(),
+            file: None,
+            inner: None,
+        }
+    }
 
-        #[cfg(not(debug_assertions))]
-        return metadata.level() <= Level::Info;
+    /// Returns the level for `target`, set by the directive with the longest matching prefix
+    fn level(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map_or(self.default_level, |(_, level)| *level)
+    }
+
+    /// Returns the maximum level any record can be logged at
+    fn max_level(&self) -> LevelFilter {
+        if self.inner.is_some() {
+            // The wrapped logger filters its records itself
+            return LevelFilter::Trace;
+        }
+
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default_level, Ord::max)
+    }
+
+    /// Installs `self` as the global logger, fails if a logger is already set
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self.max_level();
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
     }
 
     fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            let out = format!(
-                "[{} {}] {}",
-                record.level(),
-                record.target(), // Shows the module path!
-                record.args()
-            );
-            super::Console::log(out);
+        if let Some(inner) = &self.inner
+            && inner.enabled(record.metadata())
+        {
+            inner.log(record);
+        }
+
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let out = format!(
+            "[{} {}] {}",
+            record.level(),
+            record.target(), // Shows the module path!
+            record.args()
+        );
+
+        if let Some(file) = &self.file
+            && let Ok(mut file) = file.lock()
+        {
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let line = format!("{}.{:03} {out}\n", time.as_secs(), time.subsec_millis());
+            // There is nowhere to report the error, the logger would log to itself
+            let _ = file.write(line.as_bytes());
+        }
+
+        match record.level() {
+            Level::Error => Console::error(out),
+            Level::Warn => Console::warn(out),
+            _ => Console::log(out),
         }
     }
 
-    fn flush(&self) {}
+    fn flush(&self) {
+        if let Some(file) = &self.file
+            && let Ok(mut file) = file.lock()
+        {
+            let _ = file.file.flush();
+        }
+
+        if let Some(inner) = &self.inner {
+            inner.flush();
+        }
+    }
 }
 
-static LOGGER: ConsoleLogger = ConsoleLogger;
+impl ConsoleLoggerBuilder {
+    /// Sets the level of targets without a matching directive
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.default_level = level;
+        self
+    }
+
+    /// Sets the level of targets starting with the module path `prefix`, like `my_app::network`.
+    /// The directive with the longest matching prefix is used.
+    pub fn target(mut self, prefix: impl Into<String>, level: LevelFilter) -> Self {
+        let prefix = prefix.into();
+        self.directives.retain(|(p, _)| *p != prefix);
+        self.directives.push((prefix, level));
+        self
+    }
+
+    /// Parses directives in the `RUST_LOG` syntax, a comma separated list of `level`,
+    /// `target=level` or `target` (which enables all levels). Invalid directives are skipped.
+    pub fn parse(mut self, spec: &str) -> Self {
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        self = self.target(target.trim(), level);
+                    }
+                }
+                None => match directive.parse() {
+                    Ok(level) => self.default_level = level,
+                    Err(_) => self = self.target(directive, LevelFilter::Trace),
+                },
+            }
+        }
+        self
+    }
+
+    /// Parses directives from the environment variable `var`, like `RUST_LOG`. Nothing changes if
+    /// it's not set.
+    pub fn parse_env(self, var: &str) -> Self {
+        match std::env::var(var) {
+            Ok(spec) => self.parse(&spec),
+            Err(_) => self,
+        }
+    }
 
-/// Initializes the logger for the console.
+    /// Also writes the records to the file at `path`. When the file would exceed `max_size` bytes
+    /// it's rotated, keeping at most `max_files` rotated files.
+    pub fn file(mut self, path: impl Into<PathBuf>, max_size: u64, max_files: usize) -> Self {
+        self.file = Some((path.into(), max_size, max_files));
+        self
+    }
+
+    /// Forwards every record to `logger` too, it filters them by its own
+    /// [`enabled`](Log::enabled)
+    pub fn wrap(mut self, logger: impl Log + 'static) -> Self {
+        self.inner = Some(Box::new(logger));
+        self
+    }
+
+    /// Builds the logger, opening the log file if there is one
+    pub fn build(mut self) -> io::Result<ConsoleLogger> {
+        // The longest prefix is matched first
+        self.directives
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        let file = match self.file {
+            Some((path, max_size, max_files)) => {
+                Some(Mutex::new(FileSink::open(path, max_size, max_files)?))
+            }
+            None => None,
+        };
+
+        Ok(ConsoleLogger {
+            default_level: self.default_level,
+            directives: self.directives,
+            file,
+            inner: self.inner,
+        })
+    }
+
+    /// Builds the logger and installs it as the global logger
+    pub fn init(self) -> io::Result<()> {
+        self.build()?
+            .init()
+            .map_err(|err| io::Error::new(io::ErrorKind::AlreadyExists, err.to_string()))
+    }
+}
+
+impl FileSink {
+    /// Opens the file at `path` for appending
+    fn open(path: PathBuf, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    /// Returns the path of the rotated file with `index`
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    /// Writes `bytes` to the file, rotating it first if it would exceed the maximum size
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + bytes.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(bytes)?;
+        self.size += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts the rotated files and starts a new file, the oldest file is removed
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(Self::rotated_path(&self.path, self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = Self::rotated_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, Self::rotated_path(&self.path, index + 1))?;
+                }
+            }
+
+            fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Initializes the default [`ConsoleLogger`], use [`ConsoleLogger::builder`] to configure it.
 /// Has to be called before any logging occurs via the [log] crate.
 /// # Note
 /// This **has** to be called manually by the user, it does not happen automatically.
-pub fn init() {
-    log::set_logger(&LOGGER)
-        .map(|()| {
-            #[cfg(debug_assertions)]
-            return log::set_max_level(LevelFilter::Trace);
-
-            #[cfg(not(debug_assertions))]
-            return log::set_max_level(LevelFilter::Info);
-        })
-        .expect("Failed to set logger");
+pub fn init() -> Result<(), SetLoggerError> {
+    ConsoleLogger::builder()
+        .build()
+        .expect("Logger without a file can't fail to build")
+        .init()
+}
+
+#[cfg(test)]
+mod logger_tests {
+    use super::*;
+
+    #[test]
+    fn target_levels() {
+        let logger = ConsoleLogger::builder()
+            .parse("warn, tui::app=debug,tui=info ,tui::app::focus=off,bad=level,net")
+            .build()
+            .unwrap();
+
+        assert_eq!(logger.level("other"), LevelFilter::Warn);
+        assert_eq!(logger.level("tui"), LevelFilter::Info);
+        assert_eq!(logger.level("tui::canvas"), LevelFilter::Info);
+        assert_eq!(logger.level("tui::app"), LevelFilter::Debug);
+        assert_eq!(logger.level("tui::application"), LevelFilter::Info);
+        assert_eq!(logger.level("tui::app::focus"), LevelFilter::Off);
+        assert_eq!(logger.level("bad"), LevelFilter::Warn);
+        assert_eq!(logger.level("net::http"), LevelFilter::Trace);
+        assert_eq!(logger.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn file_rotation() {
+        let dir = std::env::temp_dir().join(format!("tui_logger_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+
+        let mut sink = FileSink::open(path.clone(), 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            sink.write(line.as_bytes()).unwrap();
+        }
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth\n");
+        assert_eq!(read(FileSink::rotated_path(&path, 1)), "third\n");
+        assert_eq!(read(FileSink::rotated_path(&path, 2)), "second\n");
+        assert!(!FileSink::rotated_path(&path, 3).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -1,15 +1,25 @@
+mod command;
 #[cfg(feature = "console_logger")]
 pub mod logger;
 
 use std::{
     collections::VecDeque,
-    sync::{LazyLock, RwLock},
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex, RwLock},
     time::Duration,
 };
 
-use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::{
+    event::{KeyCode, KeyModifiers},
+    style::{Attribute, Color},
+};
 
-use crate::{text::Text, *};
+use crate::{
+    text::{BufferLine, StyleSpan, Text, TextWrap},
+    *,
+};
 
 pub struct Console;
 
@@ -21,12 +31,151 @@ enum Entry {
 }
 
 impl Entry {
-    /// Returns the [text](Text) representation of [self](Entry)
-    pub fn as_text(&self) -> Text {
+    /// Width of the level prefix, continuation lines are indented by it
+    const PREFIX_WIDTH: usize = 6;
+
+    /// Returns the message of [self](Entry)
+    fn message(&self) -> &str {
+        match self {
+            Self::Info(t) | Self::Warn(t) | Self::Error(t) => t,
+        }
+    }
+
+    /// Returns the level prefix and its color
+    fn prefix(&self) -> (&'static str, Color) {
         match self {
-            Self::Info(t) => t.as_str().into(),
-            Self::Warn(t) => t.as_str().into(),
-            Self::Error(t) => t.as_str().into(),
+            Self::Info(_) => ("INFO", Color::Cyan),
+            Self::Warn(_) => ("WARN", Color::Yellow),
+            Self::Error(_) => ("ERROR", Color::Red),
+        }
+    }
+
+    /// Appends the lines of [self](Entry) to `text`, the first line starts with the colored level
+    /// prefix
+    fn push_to(&self, text: &mut Text) {
+        let (prefix, color) = self.prefix();
+        let width = Self::PREFIX_WIDTH;
+        let first_line = text.input.len();
+
+        let mut lines = self.message().lines();
+        let first = lines.next().unwrap_or_default();
+        text.input
+            .push(BufferLine::new(format!("{prefix:<width$}{first}")));
+        for line in lines {
+            text.input
+                .push(BufferLine::new(format!("{:width$}{line}", "")));
+        }
+
+        let length = prefix.len();
+        text.styles.extend([
+            StyleSpan::new(Code::Foreground(color), first_line, 0, length),
+            StyleSpan::new(Code::Attribute(Attribute::Bold), first_line, 0, length),
+        ]);
+    }
+}
+
+/// Filter of the entries shown by a [Console]
+#[derive(Debug, Clone)]
+struct Filter {
+    info: bool,
+    warn: bool,
+    error: bool,
+    /// Lowercased text the messages have to contain, ignoring case
+    text: String,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            info: true,
+            warn: true,
+            error: true,
+            text: String::new(),
+        }
+    }
+}
+
+impl Filter {
+    /// True if `entry` passes the filter
+    fn matches(&self, entry: &Entry) -> bool {
+        let level = match entry {
+            Entry::Info(_) => self.info,
+            Entry::Warn(_) => self.warn,
+            Entry::Error(_) => self.error,
+        };
+
+        level && contains_lowercase(entry.message(), &self.text)
+    }
+}
+
+/// Returns true if the lowercased `text` contains `lowercase`, without allocating
+fn contains_lowercase(text: &str, lowercase: &str) -> bool {
+    lowercase.is_empty()
+        || text.char_indices().any(|(start, _)| {
+            let mut chars = text[start..].chars().flat_map(char::to_lowercase);
+            lowercase.chars().all(|c| chars.next() == Some(c))
+        })
+}
+
+/// Entries of [HISTORY] shown in the history node of a [Console]. New entries are appended to
+/// the rendered text, instead of rendering all entries again.
+#[derive(Default)]
+struct HistoryView {
+    filter: Filter,
+    /// Ids and line counts of the shown entries, oldest first
+    shown: VecDeque<(u64, usize)>,
+    /// Id of the next entry to render
+    next: u64,
+}
+
+impl HistoryView {
+    /// Renders the entries of `history` passing the filter to the history `node` again
+    fn rebuild(&mut self, node: &mut Node, history: &History) {
+        self.shown.clear();
+        self.next = history.first_id();
+        node.text = Text::default();
+        node.text.wrap = TextWrap::Word;
+        self.update(node, history);
+    }
+
+    /// Removes the shown entries which are no longer in `history` and renders the new ones to
+    /// the history `node`. If it was scrolled to the bottom, it stays there.
+    fn update(&mut self, node: &mut Node, history: &History) {
+        let first_id = history.first_id();
+        let text = &mut node.text;
+
+        let mut removed = 0;
+        while let Some(&(id, lines)) = self.shown.front()
+            && id < first_id
+        {
+            removed += lines;
+            self.shown.pop_front();
+        }
+        if removed > 0 {
+            text.input.drain(..removed);
+            text.styles.retain_mut(|style| {
+                style.line = style.line.wrapping_sub(removed);
+                style.line < text.input.len()
+            });
+            text.prepare_edit(0, removed, 0);
+        }
+
+        // Only the appended lines are prepared, the shown ones are kept
+        let start = text.input.len();
+        for id in self.next.max(first_id)..history.pushed {
+            let entry = &history.store[(id - first_id) as usize];
+            if self.filter.matches(entry) {
+                let lines = text.input.len();
+                entry.push_to(text);
+                self.shown.push_back((id, text.input.len() - lines));
+            }
+        }
+        self.next = history.pushed;
+        text.prepare_edit(start, 0, text.input.len() - start);
+
+        if node.scroll.1 >= node.max_scroll().1 {
+            // Clamped to the last page when computed
+            node.scroll.1 = u16::MAX;
         }
     }
 }
@@ -34,26 +183,76 @@ impl Entry {
 /// History for [Console]
 struct History {
     store: VecDeque<Entry>,
+    /// Number of entries ever pushed, which is the id of the next entry
+    pushed: u64,
     version: usize,
     size: u16,
+    /// Executed command lines, oldest first
+    inputs: VecDeque<String>,
+    /// File to which executed command lines are appended
+    file: Option<PathBuf>,
 }
 
 impl History {
+    /// Maximum number of command lines kept in the input history
+    const INPUTS_SIZE: usize = 1_000;
+
     /// New Console history
     fn new() -> Self {
         Self {
             store: VecDeque::default(),
+            pushed: 0,
             version: 0,
-            // TODO: scrolling
-            // size: 1_000,
-            size: 38,
+            size: 5_000,
+            inputs: VecDeque::default(),
+            file: None,
         }
     }
 
+    /// Returns the id of the oldest entry in the store
+    fn first_id(&self) -> u64 {
+        self.pushed - self.store.len() as u64
+    }
+
     /// Ticks the version
     fn tick(&mut self) {
         self.version = self.version.wrapping_add(1);
     }
+
+    /// Pushes a new `entry`, removing the oldest entries over the size limit
+    fn push(&mut self, entry: Entry) {
+        self.store.push_back(entry);
+        self.pushed += 1;
+        self.tick();
+
+        let new_start = self.store.len().saturating_sub(self.size as usize);
+        if new_start > 0 {
+            self.store.drain(0..new_start);
+        }
+    }
+
+    /// Adds a command `line` to the input history, unless it repeats the last one. Returns the
+    /// file path and the error if it can't be appended to it, the line is kept in memory anyway.
+    fn push_input(&mut self, line: &str) -> Result<(), (PathBuf, io::Error)> {
+        if self.inputs.back().is_some_and(|last| last == line) {
+            return Ok(());
+        }
+
+        self.inputs.push_back(line.to_string());
+        if self.inputs.len() > Self::INPUTS_SIZE {
+            self.inputs.pop_front();
+        }
+
+        let Some(path) = &self.file else {
+            return Ok(());
+        };
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{line}"))
+            .map_err(|err| (path.clone(), err))
+    }
 }
 
 /// Global Console History
@@ -62,14 +261,7 @@ static HISTORY: LazyLock<RwLock<History>> = LazyLock::new(|| RwLock::new(History
 /// Push a new `entry` to Console [HISTORY]
 fn push_history(entry: Entry) {
     if let Ok(mut history) = HISTORY.write() {
-        history.store.push_back(entry);
-        history.tick();
-
-        let new_start = history.store.len().saturating_sub(history.size as usize);
-
-        if new_start > 0 {
-            history.store.drain(0..new_start);
-        }
+        history.push(entry);
     }
 }
 
@@ -165,13 +357,13 @@ impl Console {
         close_button.style.padding = (0, 1).into();
         close_button.style.bg = Some(Hsl::new(10., 1., 0.5).into());
 
-        let window_bar = window_bar.into_handle();
-        window_bar.add_child_node(label);
-        window_bar.add_child_node(close_button);
-
         // History log
+        let view = Arc::new(Mutex::new(HistoryView::default()));
         let mut history = Node::default();
         history.style.size = Size::new(SizeValue::percent(100), SizeValue::cells(HEIGHT - 2));
+        history.style.overflow.1 = Overflow::Auto;
+        history.text.wrap = TextWrap::Word;
+        let worker_view = Arc::clone(&view);
         history.start_worker(move |c| {
             let mut last_seen_version = 0;
 
@@ -183,42 +375,146 @@ impl Console {
                 }
                 last_seen_version = version;
 
-                c.send(Message::exec(|mut c| {
+                let view = Arc::clone(&worker_view);
+                c.send(Message::exec(move |mut c| {
                     let self_weak = c.self_weak.clone();
-
-                    if let Ok(history) = HISTORY.read() {
-                        let mut node = c.node_mut();
-                        node.children.clear();
-
-                        for entry in history.store.iter() {
-                            let mut entry_node = Node::default();
-                            entry_node.style.max_size = Size::parse("100%", "auto").unwrap();
-                            entry_node.text = entry.as_text();
-                            // TODO: FIX: if color is None make it inherit parent
-                            entry_node.style.bg = Some(Hsl::new(0.0, 0.0, 0.2).into());
-                            node.add_child(entry_node.into_handle(), self_weak.clone())
-                        }
+                    if let (Ok(mut view), Ok(history)) = (view.lock(), HISTORY.read()) {
+                        view.update(&mut c.node_mut(), &history);
                     }
-
                     c.app().emmit(Action::RecomputeNode(self_weak));
                 }))
                 .ok()
                 .unwrap();
             }
         });
+        let history = history.into_handle();
+
+        // Updates the filter and renders the history again
+        let update_filter = {
+            let history = history.weak();
+            move |c: &mut Context, update: &mut dyn FnMut(&mut Filter)| {
+                let (Some(node), Ok(mut view)) = (history.upgrade(), view.lock()) else {
+                    return;
+                };
+                update(&mut view.filter);
+                if let Ok(entries) = HISTORY.read() {
+                    view.rebuild(&mut node.borrow_mut(), &entries);
+                }
+                c.app.emmit(Action::RecomputeNode(history.clone()));
+            }
+        };
+
+        // Level filters, disabled levels are dimmed
+        let mut levels = Node::default();
+        levels.style.flex_row = true;
+        levels.style.gap = (1, 0);
+        let levels = levels.into_handle();
+        for entry in [
+            Entry::Info(String::new()),
+            Entry::Warn(String::new()),
+            Entry::Error(String::new()),
+        ] {
+            let (prefix, color) = entry.prefix();
+            let update_filter = update_filter.clone();
+            let mut button = Button::new(
+                prefix,
+                Some(Box::new(move |c, _, node| {
+                    let mut enabled = true;
+                    update_filter(c, &mut |filter| {
+                        let level = match entry {
+                            Entry::Info(_) => &mut filter.info,
+                            Entry::Warn(_) => &mut filter.warn,
+                            Entry::Error(_) => &mut filter.error,
+                        };
+                        *level = !*level;
+                        enabled = *level;
+                    });
+
+                    node.style.dim = !enabled;
+                    node.style.crossed = !enabled;
+                    c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
+                    true
+                })),
+            );
+            button.style.fg = Some(color);
+            levels.add_child_node(button);
+        }
+
+        // Text filter
+        let filter_options = InputOptions {
+            single_line: true,
+            on_change: Some(Box::new(move |c, value| {
+                update_filter(c, &mut |filter| filter.text = value.to_lowercase())
+            })),
+            ..Default::default()
+        };
+        let (filter_input, _) = Input::with_options("filter", filter_options);
+        {
+            let mut filter_input = filter_input.borrow_mut();
+            filter_input.style.size = Size::new(SizeValue::cells(0), SizeValue::cells(1));
+            filter_input.style.flex_grow = 1;
+        }
+
+        let window_bar = window_bar.into_handle();
+        window_bar.add_child_node(label);
+        window_bar.add_child(levels);
+        window_bar.add_child(filter_input);
+        window_bar.add_child_node(close_button);
 
         // Input field
-        let mut input = Input::new(" >");
-        input.style.size = Size::parse("100%", "1").unwrap();
-        input.style.bg = Some(Hsl::new(0.0, 0.0, 0.3).into());
+        let options = InputOptions {
+            single_line: true,
+            overflow_indicators: true,
+            on_submit: Some(Box::new(|c, line| Console::execute(c.app, line))),
+            clear_on_submit: true,
+            history: Some(Box::new(Console::input_history)),
+            ..Default::default()
+        };
+        let (input, _) = Input::with_options(" >", options);
+        {
+            let mut input = input.borrow_mut();
+            input.style.size = Size::parse("100%", "1").unwrap();
+            input.style.bg = Some(Hsl::new(0.0, 0.0, 0.3).into());
+        }
 
         // Combine window
         root.add_child(window_bar);
-        root.add_child_node(history);
-        root.add_child_node(input);
+        root.add_child(history);
+        root.add_child(input);
         root
     }
 
+    /// Persists the executed command lines to the file at `path`. Lines already in the file are
+    /// loaded into the input history, which replaces the current one, and new lines are appended
+    /// to it. A missing file is created with the first executed line.
+    pub fn set_history_file(path: impl Into<PathBuf>) -> io::Result<()> {
+        let path = path.into();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let lines = content.lines().filter(|line| !line.trim().is_empty());
+        let mut inputs = lines.map(str::to_string).collect::<VecDeque<_>>();
+        let excess = inputs.len().saturating_sub(History::INPUTS_SIZE);
+        inputs.drain(..excess);
+
+        if let Ok(mut history) = HISTORY.write() {
+            history.inputs = inputs;
+            history.file = Some(path);
+        }
+        Ok(())
+    }
+
+    /// Returns the executed command lines, oldest first
+    pub fn input_history() -> Vec<String> {
+        HISTORY
+            .read()
+            .map(|history| history.inputs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Sets the max console history size
     #[inline]
     pub fn set_history_size(size: u16) {
@@ -233,4 +529,117 @@ impl Console {
     pub fn log(text: impl Into<String>) {
         push_history(Entry::Info(text.into()))
     }
+
+    /// Logs a warning to console
+    #[inline]
+    pub fn warn(text: impl Into<String>) {
+        push_history(Entry::Warn(text.into()))
+    }
+
+    /// Logs an error to console
+    #[inline]
+    pub fn error(text: impl Into<String>) {
+        push_history(Entry::Error(text.into()))
+    }
+}
+
+#[cfg(test)]
+mod console_tests {
+    use super::*;
+
+    #[test]
+    fn entry_prefix() {
+        let mut text = Text::default();
+        Entry::Warn("first\nsecond".into()).push_to(&mut text);
+        Entry::Info(String::new()).push_to(&mut text);
+
+        let lines = text.input.iter().map(|l| l.content()).collect::<Vec<_>>();
+        assert_eq!(lines, ["WARN  first", "      second", "INFO  "]);
+        let span = &text.styles[0];
+        assert!(matches!(span.code, Code::Foreground(Color::Yellow)));
+        assert_eq!((span.line, span.character, span.length), (0, 0, 4));
+        assert_eq!(text.styles[2].line, 2);
+    }
+
+    #[test]
+    fn filter() {
+        let mut filter = Filter {
+            text: "disk".into(),
+            ..Default::default()
+        };
+        assert!(filter.matches(&Entry::Error("Disk full".into())));
+        assert!(!filter.matches(&Entry::Error("Out of memory".into())));
+
+        filter.error = false;
+        assert!(!filter.matches(&Entry::Error("Disk full".into())));
+        assert!(filter.matches(&Entry::Info("disk mounted".into())));
+
+        assert!(contains_lowercase("Größe ÄNDERN", "ändern"));
+        assert!(!contains_lowercase("Größe", "größer"));
+        assert!(contains_lowercase("", ""));
+    }
+
+    #[test]
+    fn history_view_appends_entries() {
+        let lines = |node: &Node| {
+            let input = node.text.input.iter();
+            input.map(|l| l.content().to_string()).collect::<Vec<_>>()
+        };
+        let style_lines = |node: &Node| node.text.styles.iter().map(|s| s.line).collect::<Vec<_>>();
+        let rows = |node: &Node| {
+            let visual = node.text.visual.iter();
+            visual.map(|l| (l.line_index, l.offset)).collect::<Vec<_>>()
+        };
+
+        let mut history = History::new();
+        history.size = 3;
+        let mut view = HistoryView::default();
+        let mut node = Node::default();
+
+        history.push(Entry::Info("a".into()));
+        history.push(Entry::Warn("b\nc".into()));
+        view.update(&mut node, &history);
+        assert_eq!(lines(&node), ["INFO  a", "WARN  b", "      c"]);
+        node.text.wrap_text(4);
+
+        // The oldest entry is removed from the store and the view, the kept rows are shifted and
+        // the appended lines are wrapped like them
+        history.push(Entry::Info("d".into()));
+        history.push(Entry::Error("e".into()));
+        view.update(&mut node, &history);
+        assert_eq!(lines(&node), ["WARN  b", "      c", "INFO  d", "ERROR e"]);
+        assert_eq!(style_lines(&node), [0, 0, 2, 2, 3, 3]);
+        let wrapped = [
+            (0, 0),
+            (0, 4),
+            (1, 0),
+            (1, 4),
+            (2, 0),
+            (2, 4),
+            (3, 0),
+            (3, 4),
+        ];
+        assert_eq!(rows(&node), wrapped);
+
+        view.filter.text = "d".into();
+        view.rebuild(&mut node, &history);
+        assert_eq!(lines(&node), ["INFO  d"]);
+        // The shown entry is removed, the new one is appended
+        history.push(Entry::Info("f".into()));
+        history.push(Entry::Info("dd".into()));
+        view.update(&mut node, &history);
+        assert_eq!(lines(&node), ["INFO  dd"]);
+    }
+
+    #[test]
+    fn unwritable_history_file() {
+        let mut history = History::new();
+        // A directory can't be opened for appending
+        history.file = Some(std::env::temp_dir());
+
+        assert!(history.push_input("first").is_err());
+        // Repeated lines are not saved again
+        assert!(history.push_input("first").is_ok());
+        assert_eq!(history.inputs, ["first"]);
+    }
 }
@@ -1,7 +1,9 @@
 use crossterm::{event::KeyModifiers, style::Color};
 
 use crate::{
-    Border, Node, Offset, Padding, PartialRect, Size, SizeValue, node::NodeHandle, text::Text,
+    Border, Node, Offset, Padding, PartialRect, Size, SizeValue,
+    node::NodeHandle,
+    text::{Text, TextWrap},
 };
 
 use super::{Button, Draggable, button::MouseClickHandler};
@@ -21,6 +23,7 @@ impl Dialog {
     fn message(message: &str) -> Node {
         let mut node = Node::default();
         node.text = Text::plain(message);
+        node.text.wrap = TextWrap::Word;
         node.style.max_size = Size::new(SizeValue::percent(100), SizeValue::auto());
         node
     }
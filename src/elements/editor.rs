@@ -0,0 +1,810 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Action, Context,
+    text::{BufferLine, Text},
+};
+
+/// Maximum number of undo steps kept by an [Editor]
+const HISTORY_SIZE: usize = 100;
+
+/// Line editor shared by the text editing elements. It owns the content, cursor, selection and
+/// edit history, and handles the editing keys:
+/// - Arrows, `Home` and `End` move the cursor, `Ctrl+Left/Right` jump by words and
+///   `Ctrl+Home/End` jump to the start or end of the text. With `Shift` they extend the selection.
+/// - `Backspace` and `Delete` remove graphemes, `Ctrl+W` removes the word before the cursor and
+///   `Ctrl+U` removes everything before the cursor on its line.
+/// - `Ctrl+Z` undoes an edit, `Ctrl+Y` or `Ctrl+Shift+Z` redoes it.
+/// - `Ctrl+A` selects all text, `Ctrl+C` and `Ctrl+X` copy and cut the selection to the
+///   clipboard. Without a selection, `Ctrl+C` is not handled, so it can still quit the app.
+pub(crate) struct Editor {
+    /// Content of the editor, there is always at least one line
+    pub(crate) lines: Vec<BufferLine>,
+    /// Cursor position `(grapheme, line)`
+    pub(crate) cursor: (usize, usize),
+    /// Maximum length of the content in graphemes, line breaks count as one
+    pub(crate) max_length: Option<usize>,
+    /// Selection anchor, the selection spans from it to the cursor
+    selection: Option<(usize, usize)>,
+    /// Snapshots before each edit, the last one is restored on undo
+    undo: Vec<Snapshot>,
+    /// Snapshots of undone edits, the last one is restored on redo
+    redo: Vec<Snapshot>,
+    /// Kind of the last edit, consecutive edits of the same kind are undone together
+    last_edit: Option<Edit>,
+    /// Lines changed since the last [`Editor::sync_text`], as `(start, removed, inserted)`
+    edited: Option<(usize, usize, usize)>,
+    /// Number of changes of the content, used to skip recording edits which changed nothing
+    changes: usize,
+}
+
+/// Editor content and cursor saved in the edit history
+struct Snapshot {
+    lines: Vec<String>,
+    cursor: (usize, usize),
+}
+
+/// Kind of an edit, used to group edits in the history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    /// Typing a character
+    Insert,
+    /// Removing a grapheme with `Backspace` or `Delete`
+    Remove,
+    /// Any other edit, never grouped
+    Other,
+}
+
+impl Editor {
+    /// Creates a new editor with `text`, placing the cursor at its end
+    pub(crate) fn new(text: &str) -> Self {
+        let mut editor = Self {
+            lines: vec![BufferLine::default()],
+            cursor: (0, 0),
+            max_length: None,
+            selection: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            edited: None,
+            changes: 0,
+        };
+        editor.add_string(text);
+        editor
+    }
+
+    /// Handles a key press, returns true if the key was handled
+    pub(crate) fn handle_key(&mut self, key_event: KeyEvent, c: &mut Context) -> bool {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+
+        match key_event.code {
+            // Cursor movement, a selection collapses to its start or end
+            KeyCode::Left if ctrl => self.move_to(self.word_left(self.cursor), shift),
+            KeyCode::Right if ctrl => self.move_to(self.word_right(self.cursor), shift),
+            KeyCode::Left | KeyCode::Right if !shift && self.selection_range().is_some() => {
+                let (start, end) = self.selection_range().unwrap_or_default();
+                let target = if key_event.code == KeyCode::Left {
+                    start
+                } else {
+                    end
+                };
+                self.move_to(target, false);
+            }
+            KeyCode::Left => self.move_to(self.left(self.cursor), shift),
+            KeyCode::Right => self.move_to(self.right(self.cursor), shift),
+            KeyCode::Up => self.move_to(self.vertical(self.cursor, -1), shift),
+            KeyCode::Down => self.move_to(self.vertical(self.cursor, 1), shift),
+            KeyCode::Home if ctrl => self.move_to((0, 0), shift),
+            KeyCode::Home => self.move_to((0, self.cursor.1), shift),
+            KeyCode::End if ctrl => self.move_to(self.end(), shift),
+            KeyCode::End => self.move_to((self.lines[self.cursor.1].count(), self.cursor.1), shift),
+
+            // Removing
+            KeyCode::Backspace => self.edit(Edit::Remove, |editor| {
+                if !editor.delete_selection() {
+                    editor.delete_range(editor.left(editor.cursor), editor.cursor);
+                }
+            }),
+            KeyCode::Delete => self.edit(Edit::Remove, |editor| {
+                if !editor.delete_selection() {
+                    editor.delete_range(editor.cursor, editor.right(editor.cursor));
+                }
+            }),
+            KeyCode::Char('w') if ctrl => self.edit(Edit::Other, |editor| {
+                if !editor.delete_selection() {
+                    editor.delete_range(editor.word_left(editor.cursor), editor.cursor);
+                }
+            }),
+            KeyCode::Char('u') if ctrl => self.edit(Edit::Other, |editor| {
+                if !editor.delete_selection() {
+                    editor.delete_range((0, editor.cursor.1), editor.cursor);
+                }
+            }),
+
+            // History
+            KeyCode::Char('z') if ctrl && shift => self.redo(),
+            KeyCode::Char('z') if ctrl => self.undo(),
+            KeyCode::Char('y' | 'Z') if ctrl => self.redo(),
+
+            // Selection and clipboard
+            KeyCode::Char('a') if ctrl => {
+                if self.is_empty() {
+                    return false;
+                }
+                self.select_all();
+            }
+            KeyCode::Char('c' | 'x') if ctrl => {
+                // Not handled without a selection, so `Ctrl+C` can quit the app
+                let Some(selected) = self.selected_text() else {
+                    return false;
+                };
+                c.app.emmit(Action::CopyToClipboard(selected));
+
+                if key_event.code == KeyCode::Char('x') {
+                    self.edit(Edit::Other, |editor| {
+                        editor.delete_selection();
+                    });
+                }
+            }
+
+            // Inserting, `Ctrl+Alt` is kept for `AltGr` characters
+            KeyCode::Enter | KeyCode::Char('\n') => self.insert("\n"),
+            KeyCode::Char(_) if ctrl && !alt => return false,
+            KeyCode::Char(char) => {
+                if char.is_control() || char.is_ascii_control() {
+                    return false; // Ignore control characters
+                }
+
+                self.edit(Edit::Insert, |editor| {
+                    editor.add_string(char.encode_utf8(&mut [0; 4]))
+                });
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Inserts `text` at the cursor as a single edit, replacing the selection
+    pub(crate) fn insert(&mut self, text: &str) {
+        self.edit(Edit::Other, |editor| editor.add_string(text));
+    }
+
+    /// Removes all text as a single edit
+    pub(crate) fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// Replaces all text with `text` as a single edit, placing the cursor at its end
+    pub(crate) fn set_text(&mut self, text: &str) {
+        if self.value() == text {
+            return;
+        }
+
+        self.edit(Edit::Other, |editor| {
+            editor.mark_edited(0, editor.lines.len(), 1);
+            editor.lines = vec![BufferLine::default()];
+            editor.cursor = (0, 0);
+            editor.selection = None;
+            editor.add_string(text);
+        });
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection. The text is truncated to fit
+    /// `max_length`.
+    fn add_string(&mut self, text: &str) {
+        self.delete_selection();
+
+        let mut text = text
+            .split('\n')
+            .map(|l| BufferLine::new(l.strip_suffix('\r').unwrap_or(l).to_string()))
+            .collect::<Vec<_>>();
+
+        if let Some(max_length) = self.max_length {
+            let mut remaining = max_length.saturating_sub(self.len());
+            let mut fitting = Vec::new();
+            for (i, mut line) in text.into_iter().enumerate() {
+                // Line break before the line
+                if i > 0 {
+                    if remaining == 0 {
+                        break;
+                    }
+                    remaining -= 1;
+                }
+
+                if line.count() > remaining {
+                    let end = byte_index(&line, remaining);
+                    line.remove_range(end..);
+                }
+                remaining -= line.count();
+                fitting.push(line);
+            }
+            text = fitting;
+        }
+
+        if let [line] = text.as_slice()
+            && line.content().is_empty()
+        {
+            return;
+        }
+
+        let (x, y) = self.cursor;
+        let tail = {
+            let line = &mut self.lines[y];
+            line.remove_range(byte_index(line, x)..)
+        };
+
+        let last = y + text.len() - 1;
+        let mut new_lines = text.into_iter();
+        if let Some(first) = new_lines.next() {
+            self.lines[y].push_str(first.content());
+        }
+        for (i, line) in new_lines.enumerate() {
+            self.lines.insert(y + i + 1, line);
+        }
+
+        let line = &mut self.lines[last];
+        self.cursor = (line.count(), last);
+        line.push_str(&tail);
+        self.mark_edited(y, 1, last - y + 1);
+    }
+
+    /// Removes the text between the `start` and `end` positions, placing the cursor at `start`
+    fn delete_range(&mut self, start: (usize, usize), end: (usize, usize)) {
+        if start == end {
+            return;
+        }
+
+        let tail = {
+            let line = &self.lines[end.1];
+            line.content()[byte_index(line, end.0)..].to_string()
+        };
+        self.lines.drain(start.1 + 1..=end.1);
+
+        let line = &mut self.lines[start.1];
+        line.replace_range(byte_index(line, start.0).., &tail);
+        self.cursor = start;
+        self.mark_edited(start.1, end.1 - start.1 + 1, 1);
+    }
+
+    /// Records that `removed` lines starting at `start` have been replaced by `inserted` lines,
+    /// merging it with the edits since the last sync
+    fn mark_edited(&mut self, start: usize, removed: usize, inserted: usize) {
+        self.changes += 1;
+        let Some((first_start, first_removed, first_inserted)) = self.edited else {
+            self.edited = Some((start, removed, inserted));
+            return;
+        };
+
+        // Both edits as ranges of the lines between them
+        let merged_start = first_start.min(start);
+        let merged_end = (first_start + first_inserted).max(start + removed);
+        self.edited = Some((
+            merged_start,
+            merged_end + first_removed - first_inserted - merged_start,
+            merged_end + inserted - removed - merged_start,
+        ));
+    }
+
+    /// Applies the edits since the last sync to `text`, which has to contain the lines of the
+    /// last sync. Only the edited lines are replaced and highlighted again, the text has to be
+    /// prepared afterwards.
+    ///
+    /// Returns the changed lines as `(start, removed, inserted)`, including the following lines
+    /// which were highlighted again, or `None` if nothing changed.
+    pub(crate) fn sync_text(&mut self, text: &mut Text) -> Option<(usize, usize, usize)> {
+        let edited = self.edited.take();
+        let synced_len = edited.map_or(self.lines.len(), |(_, removed, inserted)| {
+            self.lines.len() + removed - inserted
+        });
+
+        let (start, removed, inserted) = match edited {
+            Some(edited) if text.input.len() == synced_len => edited,
+            None if text.input.len() == synced_len => return None,
+            // The text was changed elsewhere, replace all lines
+            _ => (0, text.input.len(), self.lines.len()),
+        };
+
+        let lines = self.lines[start..start + inserted].to_vec();
+        text.input.splice(start..start + removed, lines);
+        let highlighted = text.highlight_edit(start, removed, inserted);
+
+        // Unchanged lines highlighted again are replaced by themselves
+        let from = start.min(highlighted.start);
+        let to = (start + inserted).max(highlighted.end);
+        Some((from, to + removed - inserted - from, to - from))
+    }
+
+    /// Returns the whole content, lines are joined with `\n`
+    pub(crate) fn value(&self) -> String {
+        self.text_between((0, 0), self.end())
+    }
+
+    /// True if the editor has no content
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].content().is_empty()
+    }
+
+    /// Returns the length of the content in graphemes, line breaks count as one
+    fn len(&self) -> usize {
+        self.lines.iter().map(|l| l.count()).sum::<usize>() + self.lines.len() - 1
+    }
+
+    /// Returns the position at the end of the text
+    fn end(&self) -> (usize, usize) {
+        let y = self.lines.len() - 1;
+        (self.lines[y].count(), y)
+    }
+
+    /// Returns the position one grapheme before `(x, y)`
+    fn left(&self, (x, y): (usize, usize)) -> (usize, usize) {
+        match (x, y) {
+            (0, 0) => (0, 0),
+            (0, y) => (self.lines[y - 1].count(), y - 1),
+            (x, y) => (x - 1, y),
+        }
+    }
+
+    /// Returns the position one grapheme after `(x, y)`
+    fn right(&self, (x, y): (usize, usize)) -> (usize, usize) {
+        if x < self.lines[y].count() {
+            (x + 1, y)
+        } else if y + 1 < self.lines.len() {
+            (0, y + 1)
+        } else {
+            (x, y)
+        }
+    }
+
+    /// Returns the position `dy` lines above or below `(x, y)`. Moving past the first or last
+    /// line goes to the start or end of the text.
+    pub(crate) fn vertical(&self, (x, y): (usize, usize), dy: isize) -> (usize, usize) {
+        let target = y as isize + dy;
+        if target < 0 {
+            (0, 0)
+        } else if target as usize >= self.lines.len() {
+            self.end()
+        } else {
+            let y = target as usize;
+            (x.min(self.lines[y].count()), y)
+        }
+    }
+
+    /// Returns the position at the start of the word before `(x, y)`
+    fn word_left(&self, (x, y): (usize, usize)) -> (usize, usize) {
+        if x == 0 {
+            return self.left((x, y));
+        }
+
+        let graphemes = self.lines[y].graphemes().take(x).collect::<Vec<_>>();
+        let mut x = x;
+        while x > 0 && !is_word(graphemes[x - 1]) {
+            x -= 1;
+        }
+        while x > 0 && is_word(graphemes[x - 1]) {
+            x -= 1;
+        }
+
+        (x, y)
+    }
+
+    /// Returns the position at the end of the word after `(x, y)`
+    fn word_right(&self, (x, y): (usize, usize)) -> (usize, usize) {
+        if x >= self.lines[y].count() {
+            return self.right((x, y));
+        }
+
+        let mut graphemes = self.lines[y].graphemes().skip(x).peekable();
+        let mut x = x;
+        while graphemes.next_if(|g| !is_word(g)).is_some() {
+            x += 1;
+        }
+        while graphemes.next_if(|g| is_word(g)).is_some() {
+            x += 1;
+        }
+
+        (x, y)
+    }
+
+    /// Moves the cursor to `target`, extending the selection if `select` is true, otherwise
+    /// clearing it
+    pub(crate) fn move_to(&mut self, target: (usize, usize), select: bool) {
+        if select {
+            self.selection.get_or_insert(self.cursor);
+        } else {
+            self.selection = None;
+        }
+
+        self.cursor = target;
+        self.last_edit = None;
+    }
+
+    /// Applies an edit of `kind` and saves the content before it to the history. Consecutive
+    /// typing or removing is grouped into a single step, edits which changed nothing are not
+    /// saved.
+    fn edit(&mut self, kind: Edit, edit: impl FnOnce(&mut Self)) {
+        let grouped =
+            kind != Edit::Other && self.selection_range().is_none() && self.last_edit == Some(kind);
+        let snapshot = (!grouped).then(|| self.snapshot());
+
+        let changes = self.changes;
+        edit(self);
+        if self.changes == changes {
+            return;
+        }
+
+        self.last_edit = Some(kind);
+        if let Some(snapshot) = snapshot {
+            self.redo.clear();
+            self.undo.push(snapshot);
+            if self.undo.len() > HISTORY_SIZE {
+                self.undo.remove(0);
+            }
+        }
+    }
+
+    /// Restores the content before the last edit
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            self.redo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    /// Restores the content of the last undone edit
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    /// Returns a snapshot of the current content
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: self.lines.iter().map(|l| l.content().to_string()).collect(),
+            cursor: self.cursor,
+        }
+    }
+
+    /// Replaces the content with `snapshot`
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.mark_edited(0, self.lines.len(), snapshot.lines.len());
+        self.lines = snapshot.lines.into_iter().map(BufferLine::new).collect();
+        self.cursor = snapshot.cursor;
+        self.selection = None;
+        self.last_edit = None;
+    }
+
+    /// Returns the ordered `(start, end)` positions of a non-empty selection
+    pub(crate) fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.selection?;
+        let (anchor, cursor) = ((anchor.1, anchor.0), (self.cursor.1, self.cursor.0));
+        if anchor == cursor {
+            return None;
+        }
+
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        Some(((start.1, start.0), (end.1, end.0)))
+    }
+
+    /// Selects all text, placing the cursor at its end
+    fn select_all(&mut self) {
+        self.selection = Some((0, 0));
+        self.cursor = self.end();
+    }
+
+    /// Returns the text between the `start` and `end` positions, lines are joined with `\n`
+    fn text_between(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        (start.1..=end.1)
+            .map(|y| {
+                let line = &self.lines[y];
+                let from = if y == start.1 { start.0 } else { 0 };
+                let to = if y == end.1 { end.0 } else { line.count() };
+                &line.content()[byte_index(line, from)..byte_index(line, to)]
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the selected text
+    fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        Some(self.text_between(start, end))
+    }
+
+    /// Removes the selected text and clears the selection. Returns true if any text was removed.
+    fn delete_selection(&mut self) -> bool {
+        let range = self.selection_range();
+        self.selection = None;
+        let Some((start, end)) = range else {
+            return false;
+        };
+
+        self.delete_range(start, end);
+        true
+    }
+
+    /// Returns the selected graphemes of each line as `(line, start, end)`
+    pub(crate) fn selected_spans(&self) -> Vec<(usize, usize, usize)> {
+        let Some((start, end)) = self.selection_range() else {
+            return Vec::new();
+        };
+
+        let lines = self.lines.iter().enumerate();
+        lines
+            .take(end.1 + 1)
+            .skip(start.1)
+            .filter_map(|(y, line)| {
+                let from = if y == start.1 { start.0 } else { 0 };
+                let to = if y == end.1 { end.0 } else { line.count() };
+                (to > from).then_some((y, from, to))
+            })
+            .collect()
+    }
+}
+
+/// Returns the byte index of the grapheme at `index`, or the line length if it's out of bounds
+fn byte_index(line: &BufferLine, index: usize) -> usize {
+    line.grapheme_to_byte_index(index)
+        .unwrap_or(line.content().len())
+}
+
+/// True if the grapheme is a part of a word, used for word jumps
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().any(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod editor_tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        elements::test_utils::{CTRL, NONE, SHIFT, key, with_context},
+        text::{Highlighter, StyleSpan},
+    };
+
+    /// Records the indices of the highlighted lines
+    struct Recording(Rc<RefCell<Vec<usize>>>);
+
+    impl Highlighter for Recording {
+        fn highlight_line(
+            &mut self,
+            _: &BufferLine,
+            line_index: usize,
+            _: &mut u32,
+        ) -> Vec<StyleSpan> {
+            self.0.borrow_mut().push(line_index);
+            Vec::new()
+        }
+    }
+
+    fn contents(text: &Text) -> Vec<&str> {
+        text.input.iter().map(|l| l.content()).collect()
+    }
+
+    #[test]
+    fn sync_highlights_edited_lines() {
+        let highlighted = Rc::new(RefCell::new(Vec::new()));
+        let mut text = Text::default();
+        text.set_highlighter(Recording(highlighted.clone()));
+
+        let mut editor = Editor::new("a\nb\nc\nd");
+        editor.sync_text(&mut text);
+        assert_eq!(highlighted.take(), [0, 1, 2, 3]);
+
+        editor.move_to((1, 1), false);
+        editor.insert("x");
+        editor.insert("y");
+        assert_eq!(editor.sync_text(&mut text), Some((1, 1, 1)));
+        assert_eq!(contents(&text), ["a", "bxy", "c", "d"]);
+        assert_eq!(highlighted.take(), [1]);
+
+        // Split a line and join two others, the lines between are highlighted once
+        editor.move_to((1, 0), false);
+        editor.insert("\n");
+        editor.move_to((0, 4), false);
+        editor.move_to((1, 3), true);
+        editor.insert("");
+        editor.sync_text(&mut text);
+        assert_eq!(contents(&text), ["a", "", "bxy", "cd"]);
+        assert_eq!(highlighted.take(), [0, 1, 2, 3]);
+
+        // Nothing changed
+        editor.move_to((0, 0), false);
+        assert_eq!(editor.sync_text(&mut text), None);
+        assert!(highlighted.take().is_empty());
+
+        editor.set_text("z");
+        editor.sync_text(&mut text);
+        assert_eq!(contents(&text), ["z"]);
+        assert_eq!(highlighted.take(), [0]);
+    }
+
+    /// Presses `keys` in order, returns true if all of them were handled
+    fn press(editor: &mut Editor, keys: &[(KeyCode, KeyModifiers)]) -> bool {
+        keys.iter().all(|&(code, modifiers)| {
+            with_context(key(code, modifiers), |c| {
+                editor.handle_key(KeyEvent::new(code, modifiers), c)
+            })
+        })
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        let keys = text
+            .chars()
+            .map(|c| (KeyCode::Char(c), NONE))
+            .collect::<Vec<_>>();
+        assert!(press(editor, &keys));
+    }
+
+    #[test]
+    fn grapheme_motion() {
+        // `e` with a combining accent and a flag are single graphemes
+        let mut editor = Editor::new("ae\u{301}\u{1F1E8}\u{1F1FF}b");
+        assert_eq!(editor.cursor, (4, 0));
+
+        press(&mut editor, &[(KeyCode::Left, NONE), (KeyCode::Left, NONE)]);
+        assert_eq!(editor.cursor, (2, 0));
+        press(&mut editor, &[(KeyCode::Backspace, NONE)]);
+        assert_eq!(editor.value(), "a\u{1F1E8}\u{1F1FF}b");
+        press(&mut editor, &[(KeyCode::Delete, NONE)]);
+        assert_eq!(editor.value(), "ab");
+        assert_eq!(editor.cursor, (1, 0));
+
+        let mut editor = Editor::new("abc\nd");
+        press(&mut editor, &[(KeyCode::Up, NONE)]);
+        assert_eq!(editor.cursor, (1, 0));
+        press(&mut editor, &[(KeyCode::End, NONE), (KeyCode::Right, NONE)]);
+        assert_eq!(editor.cursor, (0, 1));
+        press(&mut editor, &[(KeyCode::Home, CTRL)]);
+        assert_eq!(editor.cursor, (0, 0));
+    }
+
+    #[test]
+    fn word_jumps() {
+        let mut editor = Editor::new("foo bar_baz, qux");
+        press(&mut editor, &[(KeyCode::Left, CTRL)]);
+        assert_eq!(editor.cursor, (13, 0));
+        press(&mut editor, &[(KeyCode::Left, CTRL)]);
+        assert_eq!(editor.cursor, (4, 0));
+
+        press(
+            &mut editor,
+            &[(KeyCode::Home, NONE), (KeyCode::Right, CTRL)],
+        );
+        assert_eq!(editor.cursor, (3, 0));
+        press(&mut editor, &[(KeyCode::Right, CTRL)]);
+        assert_eq!(editor.cursor, (11, 0));
+    }
+
+    #[test]
+    fn delete_word_and_line() {
+        let mut editor = Editor::new("one\nfoo bar  ");
+        press(&mut editor, &[(KeyCode::Char('w'), CTRL)]);
+        assert_eq!(editor.value(), "one\nfoo ");
+        press(&mut editor, &[(KeyCode::Char('w'), CTRL)]);
+        assert_eq!(editor.value(), "one\n");
+
+        // At the start of a line the previous line break is removed
+        press(&mut editor, &[(KeyCode::Char('w'), CTRL)]);
+        assert_eq!(editor.value(), "one");
+
+        type_text(&mut editor, " two");
+        press(
+            &mut editor,
+            &[(KeyCode::Left, CTRL), (KeyCode::Char('u'), CTRL)],
+        );
+        assert_eq!(editor.value(), "two");
+        assert_eq!(editor.cursor, (0, 0));
+    }
+
+    #[test]
+    fn selection() {
+        let mut editor = Editor::new("hello world");
+        press(&mut editor, &[(KeyCode::Left, CTRL | SHIFT)]);
+        assert_eq!(editor.selected_text().as_deref(), Some("world"));
+        assert_eq!(editor.selected_spans(), [(0, 6, 11)]);
+
+        // Typing replaces the selection
+        type_text(&mut editor, "there");
+        assert_eq!(editor.value(), "hello there");
+        assert!(editor.selection_range().is_none());
+
+        // Without shift the selection collapses to its start
+        press(
+            &mut editor,
+            &[(KeyCode::Left, SHIFT), (KeyCode::Left, SHIFT)],
+        );
+        press(&mut editor, &[(KeyCode::Right, NONE)]);
+        assert_eq!(editor.cursor, (11, 0));
+
+        assert!(press(&mut editor, &[(KeyCode::Char('a'), CTRL)]));
+        assert!(press(&mut editor, &[(KeyCode::Char('x'), CTRL)]));
+        assert!(editor.is_empty());
+
+        // Nothing to copy, so `Ctrl+C` is left unhandled
+        assert!(!press(&mut editor, &[(KeyCode::Char('c'), CTRL)]));
+    }
+
+    #[test]
+    fn undo_groups_edits() {
+        let mut editor = Editor::new("");
+        type_text(&mut editor, "abc");
+        press(
+            &mut editor,
+            &[(KeyCode::Backspace, NONE), (KeyCode::Backspace, NONE)],
+        );
+        type_text(&mut editor, "xy");
+        assert_eq!(editor.value(), "axy");
+
+        press(&mut editor, &[(KeyCode::Char('z'), CTRL)]);
+        assert_eq!(editor.value(), "a");
+        press(&mut editor, &[(KeyCode::Char('z'), CTRL)]);
+        assert_eq!(editor.value(), "abc");
+        press(&mut editor, &[(KeyCode::Char('z'), CTRL)]);
+        assert_eq!(editor.value(), "");
+
+        press(&mut editor, &[(KeyCode::Char('y'), CTRL)]);
+        assert_eq!(editor.value(), "abc");
+        press(&mut editor, &[(KeyCode::Char('z'), CTRL | SHIFT)]);
+        assert_eq!(editor.value(), "a");
+
+        // A new edit clears the redo history
+        type_text(&mut editor, "b");
+        press(&mut editor, &[(KeyCode::Char('y'), CTRL)]);
+        assert_eq!(editor.value(), "ab");
+
+        // Moving the cursor ends a group
+        type_text(&mut editor, "c");
+        press(
+            &mut editor,
+            &[(KeyCode::Left, NONE), (KeyCode::Right, NONE)],
+        );
+        type_text(&mut editor, "d");
+        press(&mut editor, &[(KeyCode::Char('z'), CTRL)]);
+        assert_eq!(editor.value(), "abc");
+    }
+
+    #[test]
+    fn history_is_limited() {
+        let mut editor = Editor::new("");
+        for _ in 0..HISTORY_SIZE + 20 {
+            editor.insert("a");
+        }
+        assert_eq!(editor.undo.len(), HISTORY_SIZE);
+
+        for _ in 0..HISTORY_SIZE + 20 {
+            press(&mut editor, &[(KeyCode::Char('z'), CTRL)]);
+        }
+        assert_eq!(editor.value(), "a".repeat(20));
+    }
+
+    #[test]
+    fn noop_edits_are_not_recorded() {
+        let mut editor = Editor::new("");
+        press(
+            &mut editor,
+            &[(KeyCode::Backspace, NONE), (KeyCode::Delete, NONE)],
+        );
+        press(
+            &mut editor,
+            &[(KeyCode::Char('w'), CTRL), (KeyCode::Char('u'), CTRL)],
+        );
+        editor.insert("");
+        assert!(editor.undo.is_empty());
+
+        editor.max_length = Some(2);
+        type_text(&mut editor, "ab");
+        press(
+            &mut editor,
+            &[(KeyCode::Home, NONE), (KeyCode::Backspace, NONE)],
+        );
+        type_text(&mut editor, "c");
+        assert_eq!(editor.value(), "ab");
+        assert_eq!(editor.undo.len(), 1);
+    }
+}
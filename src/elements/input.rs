@@ -1,182 +1,685 @@
-use crossterm::event::KeyCode;
+use std::{cell::RefCell, rc::Rc};
 
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{Attribute, Color},
+};
+
+use super::{
+    completion::{Completer, Completion},
+    editor::Editor,
+    recall::{HistoryProvider, Recall},
+};
 use crate::{
-    Action, Context, Node,
-    text::{BufferLine, Text},
+    Action, Code, Context, Event, Line, Node, NodeHandle,
+    text::{BufferLine, StyleSpan, Text, TextWrap},
 };
 
-/// A simple text input element that allows for multi-line input.
+pub type SubmitHandler = Box<dyn FnMut(&mut Context, &str)>;
+pub type ChangeHandler = Box<dyn FnMut(&mut Context, &str)>;
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// Options for [`Input::with_options`]
+#[derive(Default)]
+pub struct InputOptions {
+    /// `Enter` calls `on_submit` instead of inserting a new line, line breaks in pasted text are
+    /// replaced with spaces. The text doesn't wrap, if the node has a fixed width it scrolls
+    /// horizontally to keep the cursor visible
+    pub single_line: bool,
+    /// Called with the value when `Enter` is pressed in single-line mode and the value is valid
+    pub on_submit: Option<SubmitHandler>,
+    /// Clear the input after `on_submit` is called, the cleared text can be restored with undo
+    pub clear_on_submit: bool,
+    /// Called with the new value after every change
+    pub on_change: Option<ChangeHandler>,
+    /// Character drawn instead of each grapheme, like `'*'` for passwords. Masked text can't be
+    /// copied
+    pub mask: Option<char>,
+    /// Maximum length of the value in graphemes, line breaks count as one
+    pub max_length: Option<usize>,
+    /// Show an ellipsis at the edges of a horizontally scrolled single-line input, where its
+    /// text continues
+    pub overflow_indicators: bool,
+    /// Returns an error message for invalid values. Invalid inputs have the [`Input::INVALID_CLASS`]
+    /// class and their text is underlined with red squiggles
+    pub validator: Option<Validator>,
+    /// Source of completions shown in a dropdown under the cursor after every edit, or when
+    /// `Ctrl+Space` is pressed
+    pub completer: Option<Completer>,
+    /// Returns the previously submitted values, oldest first. In single-line mode `Up` and
+    /// `Down` recall them and `Ctrl+R` searches them in reverse
+    pub history: Option<HistoryProvider>,
+}
+
+/// Shared handle to the value of an [Input], returned by [`Input::with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct InputValue(Rc<RefCell<ValueState>>);
+
+#[derive(Debug, Default)]
+struct ValueState {
+    value: String,
+    error: Option<String>,
+}
+
+impl InputValue {
+    /// Returns the current value, lines are joined with `\n`
+    pub fn value(&self) -> String {
+        self.0.borrow().value.clone()
+    }
+
+    /// Returns the validator error of the current value
+    pub fn error(&self) -> Option<String> {
+        self.0.borrow().error.clone()
+    }
+
+    /// True if the current value is accepted by the validator
+    pub fn is_valid(&self) -> bool {
+        self.0.borrow().error.is_none()
+    }
+
+    /// Replaces the value and its validator error
+    pub(crate) fn set(&self, value: String, error: Option<String>) {
+        *self.0.borrow_mut() = ValueState { value, error };
+    }
+}
+
+/// A text input element that allows for multi-line input, or single-line input configured with
+/// [`InputOptions`]. See [Editor] for the editing keys. Additionally:
+/// - In single-line mode `Enter` submits the value, and the text scrolls horizontally instead of
+///   wrapping.
+/// - With a mask, each grapheme is drawn as the mask character and the text can't be copied.
+/// - With a [`Completer`], `Up` and `Down` select a completion, `Tab` or `Enter` accept it and
+///   `Esc` closes the dropdown.
+/// - With a history in single-line mode, `Up` and `Down` recall older and newer values. `Ctrl+R`
+///   starts a reverse search, typing edits the query and `Ctrl+R` finds an older match, `Enter`
+///   accepts the match and `Esc` cancels the search.
+///
 /// The struct itself is used inside the [node's](Node) event handler.
 pub struct Input {
     placeholder: String,
-    visible_placeholder: bool,
-    cursor: (usize, usize),
+    editor: Editor,
+    /// Column of the first visible column in single-line mode
+    scroll: usize,
+    options: InputOptions,
+    value: InputValue,
+    completion: Option<Completion>,
+    recall: Option<Recall>,
+    /// Whether to request (true) or dismiss (false) completions after the current event, by
+    /// default they are requested after an edit
+    complete: Option<bool>,
 }
 
 impl Input {
-    pub fn new(placeholder: &str) -> Node {
+    /// Class added to the input node while its value is rejected by the validator
+    pub const INVALID_CLASS: &str = "invalid";
+
+    /// Creates a new multi-line input [`NodeHandle`] showing `placeholder` while it's empty
+    pub fn new(placeholder: &str) -> NodeHandle {
+        Self::with_options(placeholder, InputOptions::default()).0
+    }
+
+    /// Creates a new input [`NodeHandle`] configured with `options`, and a handle to its value
+    pub fn with_options(placeholder: &str, mut options: InputOptions) -> (NodeHandle, InputValue) {
         let mut root = Node::default();
         root.text = placeholder.into();
         root.text.cursor = Some((0, 0));
 
+        if options.single_line {
+            root.text.wrap = TextWrap::None;
+        }
+
+        let value = InputValue::default();
+        let mut editor = Editor::new("");
+        editor.max_length = options.max_length;
+        let completion = options
+            .completer
+            .take()
+            .map(|completer| Completion::new(completer, &mut root));
+        let recall = options.history.take().map(Recall::new);
+
         let mut input = Self {
             placeholder: placeholder.to_string(),
-            visible_placeholder: true,
-            cursor: (0, 0),
+            editor,
+            scroll: 0,
+            options,
+            value: value.clone(),
+            completion,
+            recall,
+            complete: None,
         };
+        input.validate(&mut root);
 
         let handler = move |c: &mut Context, node: &mut Node| {
-            let mut stop_propagation = false;
-
-            if let Some(paste) = c.event.as_paste_event() {
-                // TODO implement auto splitting of lines in [`TextInput`] or [`Text`]
-                input.add_string(paste, node);
-                stop_propagation = true;
+            if let Some(completion) = &mut input.completion {
+                completion.poll();
+                if matches!(c.event, Event::NodeFocusLost) && completion.is_open() {
+                    completion.dismiss(c);
+                }
             }
 
-            let Some(key_event) = c.event.as_key_event() else {
-                if stop_propagation {
-                    input.process_text(node);
-                    c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
-                }
-                return stop_propagation;
-            };
+            let before = input.editor.lines.clone();
+            let cursor = input.editor.cursor;
 
-            if key_event.code == KeyCode::Enter {
-                input.add_new_line(node);
-                stop_propagation = true;
-            }
+            if let Some(paste) = c.event.as_paste_event() {
+                let paste = if input.options.single_line {
+                    paste.lines().collect::<Vec<_>>().join(" ")
+                } else {
+                    paste.to_string()
+                };
 
-            if key_event.code == KeyCode::Backspace {
-                input.remove_char(node);
-                stop_propagation = true;
+                match &mut input.recall {
+                    Some(recall) if recall.is_searching() => {
+                        recall.edit_query(|query| query.push_str(&paste))
+                    }
+                    recall => {
+                        recall.as_mut().map(Recall::reset);
+                        input.editor.insert(&paste);
+                    }
+                }
+            } else if let Some(key_event) = c.event.as_key_event() {
+                if key_event.kind == KeyEventKind::Release || !input.handle_key(key_event, c) {
+                    return false;
+                }
+            } else {
+                return false;
             }
 
-            let Some(char) = key_event.code.as_char() else {
-                if stop_propagation {
-                    input.process_text(node);
-                    c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
+            let changed = before.len() != input.editor.lines.len()
+                || before
+                    .iter()
+                    .zip(&input.editor.lines)
+                    .any(|(a, b)| a.content() != b.content());
+            if changed {
+                input.validate(node);
+                if let Some(on_change) = &mut input.options.on_change {
+                    on_change(c, &input.value.value());
                 }
-                return stop_propagation;
-            };
-
-            if char.is_control() || char.is_ascii_control() {
-                return false; // Ignore control characters
             }
 
-            if char == '\n' {
-                input.add_new_line(node);
-            } else {
-                input.add_char(char, node);
+            input.process_text(node);
+
+            if let Some(completion) = &mut input.completion {
+                if input.complete.take().unwrap_or(changed) {
+                    let (x, y) = input.editor.cursor;
+                    let line = input.editor.lines[y].content();
+                    let position = node.text.cursor.unwrap_or_default();
+                    completion.request(line, x, position, c, node);
+                } else if input.editor.cursor != cursor && completion.is_open() {
+                    completion.dismiss(c);
+                }
             }
 
-            input.process_text(node);
             c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
             true
         };
 
         root.add_handler(handler, true);
-        root
+        (root.into_handle(), value)
     }
 
-    fn add_new_line(&mut self, node: &mut Node) {
-        self.cursor.0 = 0;
-        self.cursor.1 += 1;
-        if self.visible_placeholder {
-            node.text = "\n".into();
-            self.visible_placeholder = false;
+    /// Handles a key press, returns true if the key was handled
+    fn handle_key(&mut self, key_event: KeyEvent, c: &mut Context) -> bool {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+        if self.recall.as_ref().is_some_and(Recall::is_searching)
+            && let Some(handled) = self.handle_recall_key(key_event)
+        {
+            return handled;
+        }
+
+        if let Some(completion) = &mut self.completion {
+            let open = completion.is_open();
+            match key_event.code {
+                KeyCode::Char(' ') if ctrl => self.complete = Some(true),
+                KeyCode::Up if open => completion.move_selection(-1, c),
+                KeyCode::Down if open => completion.move_selection(1, c),
+                KeyCode::Esc if open => {
+                    completion.dismiss(c);
+                    self.complete = Some(false);
+                }
+                KeyCode::Tab | KeyCode::Enter if open && key_event.modifiers.is_empty() => {
+                    if let Some((start, candidate)) = completion.selected() {
+                        let candidate = candidate.to_string();
+                        let (x, y) = self.editor.cursor;
+                        self.editor.move_to((start.min(x), y), false);
+                        self.editor.move_to((x, y), true);
+                        self.editor.insert(&candidate);
+                    }
+                    completion.dismiss(c);
+                    self.complete = Some(false);
+                }
+                _ => {
+                    return self
+                        .handle_recall_key(key_event)
+                        .unwrap_or_else(|| self.handle_edit_key(key_event, c));
+                }
+            }
+            return true;
+        }
+
+        self.handle_recall_key(key_event)
+            .unwrap_or_else(|| self.handle_edit_key(key_event, c))
+    }
+
+    /// Handles a key press of the history recall, returns `None` if the key should be handled by
+    /// the line editor
+    fn handle_recall_key(&mut self, key_event: KeyEvent) -> Option<bool> {
+        let Some(recall) = &mut self.recall else {
+            return None;
+        };
+        if !self.options.single_line {
+            return None;
+        }
+
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+        let current = self.editor.value();
+
+        let (value, handled) = if recall.is_searching() {
+            match key_event.code {
+                KeyCode::Char('r') if ctrl => {
+                    recall.search(&current);
+                    return Some(true);
+                }
+                KeyCode::Char(char) if !ctrl && !alt => {
+                    recall.edit_query(|query| query.push(char));
+                    return Some(true);
+                }
+                KeyCode::Backspace => {
+                    recall.edit_query(|query| {
+                        query.pop();
+                    });
+                    return Some(true);
+                }
+                KeyCode::Esc => (recall.finish_search(false), Some(true)),
+                KeyCode::Enter => (recall.finish_search(true), Some(true)),
+                // Other keys accept the match and are handled by the editor
+                _ => (recall.finish_search(true), None),
+            }
         } else {
-            node.text.input.insert(self.cursor.1, BufferLine::default());
+            match key_event.code {
+                KeyCode::Char('r') if ctrl => {
+                    recall.search(&current);
+                    return Some(true);
+                }
+                KeyCode::Up | KeyCode::Down if key_event.modifiers.is_empty() => {
+                    let value = recall.navigate(key_event.code == KeyCode::Up, &current);
+                    let handled = value.is_some().then_some(true);
+                    (value, handled)
+                }
+                _ => {
+                    recall.reset();
+                    return None;
+                }
+            }
+        };
+
+        if let Some(value) = value {
+            self.editor.set_text(&value);
+            self.complete = Some(false);
         }
+        handled
     }
 
-    fn add_char(&mut self, char: char, node: &mut Node) {
-        if self.visible_placeholder {
-            node.text = "".into();
-            self.visible_placeholder = false;
+    /// Handles a key press of the line editor, returns true if the key was handled
+    fn handle_edit_key(&mut self, key_event: KeyEvent, c: &mut Context) -> bool {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key_event.code {
+            // Masked text can't be copied
+            KeyCode::Char('c' | 'x') if ctrl && self.options.mask.is_some() => false,
+            KeyCode::Enter | KeyCode::Char('\n') if self.options.single_line => {
+                let value = self.value.value();
+                if let Some(on_submit) = &mut self.options.on_submit
+                    && self.value.is_valid()
+                {
+                    on_submit(c, &value);
+                    if self.options.clear_on_submit {
+                        self.editor.clear();
+                    }
+                }
+                true
+            }
+            _ => self.editor.handle_key(key_event, c),
         }
+    }
+
+    /// Updates the shared value and validates it, marking the node if it's invalid
+    fn validate(&mut self, node: &mut Node) {
+        let value = self.editor.value();
+        let error = self
+            .options
+            .validator
+            .as_ref()
+            .and_then(|validator| validator(&value).err());
 
-        let line = &mut node.text.input[self.cursor.1];
-        let index = line
-            .grapheme_to_byte_index(self.cursor.0)
-            .unwrap_or(line.content().len());
-        line.insert(index, char);
+        let mut classes = node
+            .class
+            .split_whitespace()
+            .filter(|class| *class != Self::INVALID_CLASS)
+            .collect::<Vec<_>>();
+        if error.is_some() {
+            classes.push(Self::INVALID_CLASS);
+        }
+        node.class = classes.join(" ");
 
-        self.cursor.0 += 1;
+        self.value.set(value, error);
     }
 
-    fn add_string(&mut self, text: &str, node: &mut Node) {
-        if self.visible_placeholder {
-            node.text = text.into();
-            let lines_len = node.text.input.len();
-            let final_line_len = node.text.input.last().map_or(0, |l| l.count());
-            self.cursor = (final_line_len, lines_len.saturating_sub(1));
-            self.visible_placeholder = false;
+    fn process_text(&mut self, node: &mut Node) {
+        node.text.styles.clear();
+
+        if let Some((query, matched)) = self.recall.as_ref().and_then(Recall::search_state) {
+            let prompt = format!("{}`{query}`", Recall::SEARCH_PROMPT);
+            let column = BufferLine::new(prompt.clone()).width() - 1;
+            let text = format!("{prompt}: {}", matched.unwrap_or_default());
+
+            node.text.input = vec![BufferLine::new(text)];
+            node.text.cursor = Some((column as u16, 0));
+            node.text.prepare_text(u16::MAX);
             return;
         }
 
-        let text: Text = text.into();
-        let new_lines = text.input;
-        let lines = &mut node.text.input;
+        if self.editor.is_empty() {
+            node.text.input = Text::plain(&self.placeholder).input;
+            node.text.cursor = Some((0, 0));
+            node.text.prepare_text(u16::MAX);
+            return;
+        }
 
-        if new_lines.len() == 1 {
-            let line = &mut lines[self.cursor.1];
-            let index = line
-                .grapheme_to_byte_index(self.cursor.0)
-                .unwrap_or(line.content().len());
-            line.insert_str(index, new_lines[0].content());
-            self.cursor.0 += new_lines[0].count();
-        } else {
-            let old_line = lines.remove(self.cursor.1);
-            let mid_index = old_line
-                .grapheme_to_byte_index(self.cursor.0)
-                .unwrap_or(old_line.content().len());
-            let (left_part, right_part) = old_line.content().split_at(mid_index);
-
-            self.cursor.0 = new_lines.last().map(|l| l.count()).unwrap_or(0);
-
-            let original_y = self.cursor.1;
-            for line in new_lines {
-                lines.insert(self.cursor.1, line);
-                self.cursor.1 += 1;
-            }
-            self.cursor.1 -= 1; // Adjust to the last inserted line
+        node.text.input = match self.options.mask {
+            Some(mask) => self
+                .editor
+                .lines
+                .iter()
+                .map(|l| BufferLine::new(mask.to_string().repeat(l.count())))
+                .collect(),
+            None => self.editor.lines.clone(),
+        };
 
-            if !left_part.is_empty() {
-                lines[original_y].insert_str(0, left_part);
+        // Underline invalid text
+        if !self.value.is_valid() {
+            for (y, line) in self.editor.lines.iter().enumerate() {
+                let length = line.count();
+                node.text.styles.extend([
+                    StyleSpan::new(Code::Attribute(Attribute::Undercurled), y, 0, length),
+                    StyleSpan::new(Code::UnderlineColor(Color::Red), y, 0, length),
+                ]);
             }
+        }
 
-            if !right_part.is_empty() {
-                lines[self.cursor.1].push_str(right_part);
-            }
+        // Highlight the selection
+        for (y, from, to) in self.editor.selected_spans() {
+            let code = Code::Attribute(Attribute::Reverse);
+            node.text
+                .styles
+                .push(StyleSpan::new(code, y, from, to - from));
+        }
+
+        // The rendered cursor is in columns
+        let (x, y) = self.editor.cursor;
+        let mut column = node.text.input.get(y).map_or(0, |line| {
+            line.grapheme_data()
+                .iter()
+                .take(x)
+                .map(|(.., width)| width)
+                .sum()
+        });
+
+        // Fixed width single-line inputs scroll horizontally
+        let width = node.style.clamped_width() as usize;
+        if self.options.single_line && !node.style.size.width.is_auto() && width > 0 {
+            column = self.scroll_to(column, width, node);
+        } else {
+            self.scroll = 0;
         }
+
+        node.text.prepare_text(u16::MAX);
+        node.text.cursor = Some((column as u16, y as u16));
     }
 
-    fn remove_char(&mut self, node: &mut Node) {
-        let lines = &mut node.text.input;
-        if self.cursor.0 > 0 {
-            self.cursor.0 -= 1;
-            lines[self.cursor.1].remove(self.cursor.0);
-        } else if self.cursor.1 > 0 {
-            let prev_line = lines.remove(self.cursor.1);
-            self.cursor.1 -= 1;
-            self.cursor.0 = lines[self.cursor.1].count();
-            lines[self.cursor.1].push_str(prev_line.content());
+    /// Scrolls the single-line text so the cursor `column` is visible in `width` columns, and
+    /// replaces the node's text with the visible part. Returns the visible cursor column.
+    fn scroll_to(&mut self, column: usize, width: usize, node: &mut Node) -> usize {
+        let line = &node.text.input[0];
+        let text_width = line.width();
+        let indicator = self.options.overflow_indicators as usize;
+
+        // Keep the cursor visible, it can be after the last grapheme
+        if column < self.scroll {
+            self.scroll = column;
+        }
+        let available = width.saturating_sub(2 * indicator).max(1);
+        if column >= self.scroll + available {
+            self.scroll = column + 1 - available;
         }
+        // Don't leave empty space after the text
+        let max_scroll = (text_width + 1).saturating_sub(width.saturating_sub(indicator));
+        self.scroll = self.scroll.min(max_scroll);
 
-        if lines.len() == 1 && lines[0].content().is_empty() {
-            self.visible_placeholder = true;
+        let left = if self.scroll > 0 { indicator } else { 0 };
+        let mut available = width.saturating_sub(left);
+        let right = if text_width > self.scroll + available {
+            indicator
+        } else {
+            0
+        };
+        available = available.saturating_sub(right);
+
+        // Graphemes fully inside the visible columns
+        let mut start = None;
+        let mut visible = 0..0;
+        let mut x = 0;
+        for (i, (.., grapheme_width)) in line.grapheme_data().iter().enumerate() {
+            if x >= self.scroll && x + grapheme_width <= self.scroll + available {
+                if start.is_none() {
+                    start = Some(x);
+                    visible.start = i;
+                }
+                visible.end = i + 1;
+            }
+            x += grapheme_width;
         }
+
+        // A wide grapheme cut at the start is replaced with spaces
+        let padding = start.map_or(0, |start| start - self.scroll);
+        let offset = left + padding;
+
+        let mut content = String::new();
+        if left > 0 {
+            content.push_str(Line::ELLIPSIS);
+        }
+        content.push_str(&" ".repeat(padding));
+        if let Some(slice) = line.slice(visible.start, visible.end) {
+            content.push_str(slice);
+        }
+        if right > 0 {
+            content.push_str(Line::ELLIPSIS);
+        }
+        node.text.input[0] = BufferLine::new(content);
+
+        // Move the styles with the visible graphemes
+        node.text.styles.retain_mut(|style| {
+            let from = style.character.max(visible.start);
+            let to = (style.character + style.length).min(visible.end);
+            style.character = from.saturating_sub(visible.start) + offset;
+            style.length = to.saturating_sub(from);
+            style.length > 0
+        });
+
+        column - self.scroll + left
     }
+}
 
-    fn process_text(&mut self, node: &mut Node) {
-        if self.visible_placeholder {
-            node.text = self.placeholder.clone().into();
-            self.cursor = (0, 0);
-        } else {
-            // node.text.prepare_text(u16::MAX);
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use crate::{
+        AppContext, NodeHandle, Size,
+        elements::test_utils::{CTRL, NONE, dispatch, key, type_text},
+    };
+
+    fn input(options: InputOptions) -> (NodeHandle, InputValue, AppContext) {
+        let (node, value) = Input::with_options("", options);
+        let app = AppContext::new(&node, (80, 24));
+        (node, value, app)
+    }
+
+    fn visible(node: &NodeHandle) -> String {
+        node.borrow().text.input[0].content().to_string()
+    }
+
+    #[test]
+    fn single_line_submit() {
+        let submitted = Rc::new(RefCell::new(Vec::new()));
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let (node, value, mut app) = input(InputOptions {
+            single_line: true,
+            clear_on_submit: true,
+            on_submit: Some(Box::new({
+                let submitted = submitted.clone();
+                move |_, value| submitted.borrow_mut().push(value.to_string())
+            })),
+            on_change: Some(Box::new({
+                let changes = changes.clone();
+                move |_, value| changes.borrow_mut().push(value.to_string())
+            })),
+            ..Default::default()
+        });
+
+        // Line breaks are pasted as spaces
+        assert!(dispatch(&node, &mut app, Event::Paste("a\nb".into())));
+        assert_eq!(value.value(), "a b");
+
+        assert!(dispatch(&node, &mut app, key(KeyCode::Enter, NONE)));
+        assert_eq!(*submitted.borrow(), ["a b"]);
+        assert_eq!(value.value(), "");
+
+        // The cleared text can be restored
+        dispatch(&node, &mut app, key(KeyCode::Char('z'), CTRL));
+        assert_eq!(value.value(), "a b");
+        assert_eq!(*changes.borrow(), ["a b", "", "a b"]);
+    }
+
+    #[test]
+    fn mask() {
+        let (node, value, mut app) = input(InputOptions {
+            mask: Some('*'),
+            ..Default::default()
+        });
+
+        type_text(&node, &mut app, "pw1");
+        assert_eq!(value.value(), "pw1");
+        assert_eq!(visible(&node), "***");
+
+        // Masked text can't be copied
+        assert!(dispatch(&node, &mut app, key(KeyCode::Char('a'), CTRL)));
+        assert!(!dispatch(&node, &mut app, key(KeyCode::Char('c'), CTRL)));
+        assert!(!dispatch(&node, &mut app, key(KeyCode::Char('x'), CTRL)));
+        assert_eq!(value.value(), "pw1");
+    }
+
+    #[test]
+    fn max_length() {
+        let (node, value, mut app) = input(InputOptions {
+            max_length: Some(3),
+            ..Default::default()
+        });
+
+        type_text(&node, &mut app, "abcd");
+        assert_eq!(value.value(), "abc");
+
+        dispatch(&node, &mut app, key(KeyCode::Left, NONE));
+        dispatch(&node, &mut app, Event::Paste("xyz".into()));
+        assert_eq!(value.value(), "abc");
+
+        dispatch(&node, &mut app, key(KeyCode::Backspace, NONE));
+        dispatch(&node, &mut app, Event::Paste("xyz".into()));
+        assert_eq!(value.value(), "axc");
+    }
+
+    #[test]
+    fn validator() {
+        let submitted = Rc::new(RefCell::new(Vec::new()));
+        let (node, value, mut app) = input(InputOptions {
+            single_line: true,
+            validator: Some(Box::new(|value| {
+                if value.len() < 3 {
+                    Err("Too short".to_string())
+                } else {
+                    Ok(())
+                }
+            })),
+            on_submit: Some(Box::new({
+                let submitted = submitted.clone();
+                move |_, value| submitted.borrow_mut().push(value.to_string())
+            })),
+            ..Default::default()
+        });
+        let is_invalid = |node: &NodeHandle| {
+            let node = node.borrow();
+            node.class
+                .split_whitespace()
+                .any(|c| c == Input::INVALID_CLASS)
+        };
+
+        assert!(is_invalid(&node));
+        type_text(&node, &mut app, "ab");
+        assert_eq!(value.error().as_deref(), Some("Too short"));
+
+        // Invalid values are not submitted
+        dispatch(&node, &mut app, key(KeyCode::Enter, NONE));
+        assert!(submitted.borrow().is_empty());
+
+        type_text(&node, &mut app, "c");
+        assert!(value.is_valid());
+        assert!(!is_invalid(&node));
+        dispatch(&node, &mut app, key(KeyCode::Enter, NONE));
+        assert_eq!(*submitted.borrow(), ["abc"]);
+    }
+
+    #[test]
+    fn scroll_to_cursor() {
+        let (node, _, mut app) = input(InputOptions {
+            single_line: true,
+            ..Default::default()
+        });
+        node.borrow_mut().style.size = Size::from_cells(5, 1);
+        node.borrow_mut().style.max_size = Size::from_cells(5, 1);
+
+        type_text(&node, &mut app, "abcdefgh");
+        assert_eq!(visible(&node), "efgh");
+        assert_eq!(node.borrow().text.cursor, Some((4, 0)));
+
+        dispatch(&node, &mut app, key(KeyCode::Home, NONE));
+        assert_eq!(visible(&node), "abcde");
+        assert_eq!(node.borrow().text.cursor, Some((0, 0)));
+
+        // Moving left of the visible text scrolls just enough
+        dispatch(&node, &mut app, key(KeyCode::End, NONE));
+        for _ in 0..5 {
+            dispatch(&node, &mut app, key(KeyCode::Left, NONE));
         }
+        assert_eq!(visible(&node), "defgh");
+        assert_eq!(node.borrow().text.cursor, Some((0, 0)));
+    }
+
+    #[test]
+    fn scroll_with_overflow_indicators() {
+        let (node, _, mut app) = input(InputOptions {
+            single_line: true,
+            overflow_indicators: true,
+            ..Default::default()
+        });
+        node.borrow_mut().style.size = Size::from_cells(5, 1);
+        node.borrow_mut().style.max_size = Size::from_cells(5, 1);
+
+        type_text(&node, &mut app, "abcdefgh");
+        assert_eq!(visible(&node), format!("{}fgh", Line::ELLIPSIS));
+        assert_eq!(node.borrow().text.cursor, Some((4, 0)));
 
-        node.text.cursor = Some((self.cursor.0 as u16, self.cursor.1 as u16));
+        dispatch(&node, &mut app, key(KeyCode::Home, NONE));
+        assert_eq!(visible(&node), format!("abcd{}", Line::ELLIPSIS));
+        assert_eq!(node.borrow().text.cursor, Some((0, 0)));
     }
 }
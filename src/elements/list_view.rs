@@ -0,0 +1,541 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    collections::BTreeSet,
+    rc::Rc,
+};
+
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind},
+    style::Color,
+};
+
+use crate::{
+    Action, App, AppContext, Context, Node, NodeHandle, Size, SizeValue, WeakNodeHandle, text::Text,
+};
+
+pub type ActivateHandler = Box<dyn FnMut(&mut Context, usize)>;
+
+/// Source of the items shown in a [ListView]
+pub trait ListSource {
+    /// Returns the number of items
+    fn len(&self) -> usize;
+
+    /// Returns true if there are no items
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Builds the node of the item at `index`. It's called only for visible items, every time
+    /// the list changes. The node is sized to a row of the list.
+    fn render(&mut self, index: usize) -> Node;
+}
+
+impl<T: AsRef<str>> ListSource for Vec<T> {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn render(&mut self, index: usize) -> Node {
+        let mut node = Node::default();
+        node.text = Text::plain(self[index].as_ref());
+        node
+    }
+}
+
+/// Selection behavior of a [ListView]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListSelection {
+    /// The item under the cursor is selected, initially the first item
+    #[default]
+    Single,
+    /// Any items can be selected. `Shift` extends the selection to the cursor, `Ctrl` moves the
+    /// cursor without selecting, `Space` and `Ctrl+Click` toggle an item, `Ctrl+A` selects all
+    Multiple,
+}
+
+/// Options for [`ListView::with_options`]
+pub struct ListViewOptions {
+    pub selection: ListSelection,
+    /// Height of every item in rows
+    pub item_height: u16,
+    /// Called with the index of the item under the cursor when `Enter` is pressed
+    pub on_activate: Option<ActivateHandler>,
+}
+
+impl Default for ListViewOptions {
+    fn default() -> Self {
+        Self {
+            selection: ListSelection::default(),
+            item_height: 1,
+            on_activate: None,
+        }
+    }
+}
+
+/// How moving the cursor changes the selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Select {
+    /// Selects only the cursor item
+    Replace,
+    /// Selects the items from the anchor to the cursor
+    Extend,
+    /// Toggles the cursor item
+    Toggle,
+    /// Keeps the selection
+    Keep,
+}
+
+/// Shared handle to a [ListView], returned by [`ListView::with_options`]. It gives access to the
+/// source and the selection.
+pub struct ListHandle<S>(Rc<RefCell<ListState<S>>>);
+
+impl<S> Clone for ListHandle<S> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+/// State of a [ListView], shared by its node and [`ListHandle`]
+struct ListState<S> {
+    source: S,
+    options: ListViewOptions,
+    /// Index of the item under the cursor
+    cursor: usize,
+    selected: BTreeSet<usize>,
+    /// Item where a range selection starts
+    anchor: usize,
+    /// Index of the first visible item
+    top: usize,
+    /// The list node, children are the visible items
+    root: WeakNodeHandle,
+}
+
+/// A vertically scrolling list of items from a [`ListSource`], sized to its parent. Only the
+/// visible items have nodes, so it can show large sources.
+/// - `Up`, `Down`, `PageUp`, `PageDown`, `Home` and `End` move the cursor, the view scrolls to
+///   keep it visible. The mouse wheel scrolls the view.
+/// - Clicking an item moves the cursor to it, `Enter` activates the item under the cursor.
+///
+/// See [`ListSelection`] for the selection keys.
+pub struct ListView;
+
+impl ListView {
+    const SELECTED_COLOR: Color = Color::Rgb {
+        r: 60,
+        g: 60,
+        b: 110,
+    };
+    const CURSOR_COLOR: Color = Color::Rgb {
+        r: 40,
+        g: 40,
+        b: 50,
+    };
+
+    /// Creates a new list view [`NodeHandle`] showing the items of `source`, and a handle to it
+    pub fn new<S: ListSource + 'static>(source: S) -> (NodeHandle, ListHandle<S>) {
+        Self::with_options(source, ListViewOptions::default())
+    }
+
+    /// Creates a new list view [`NodeHandle`] showing the items of `source` configured with
+    /// `options`, and a handle to it
+    pub fn with_options<S: ListSource + 'static>(
+        source: S,
+        mut options: ListViewOptions,
+    ) -> (NodeHandle, ListHandle<S>) {
+        options.item_height = options.item_height.max(1);
+
+        let mut root = Node::default();
+        root.style.size = Size::new(SizeValue::percent(100), SizeValue::percent(100));
+        let root = root.into_handle();
+
+        let state = ListState {
+            source,
+            options,
+            cursor: 0,
+            selected: BTreeSet::new(),
+            anchor: 0,
+            top: 0,
+            root: root.weak(),
+        };
+        let handle = ListHandle(Rc::new(RefCell::new(state)));
+
+        handle.0.borrow_mut().render(&mut root.borrow_mut());
+
+        let state = Rc::clone(&handle.0);
+        let handler = move |c: &mut Context, node: &mut Node| {
+            let Ok(mut list) = state.try_borrow_mut() else {
+                return false;
+            };
+
+            if let Some(key_event) = c.event.as_key_event() {
+                if key_event.kind == KeyEventKind::Release {
+                    return false;
+                }
+                if key_event.code == KeyCode::Enter && key_event.modifiers.is_empty() {
+                    if list.source.is_empty() {
+                        return false;
+                    }
+                    // The handler can use the list handle
+                    let cursor = list.cursor;
+                    let on_activate = list.options.on_activate.take();
+                    drop(list);
+                    if let Some(mut on_activate) = on_activate {
+                        on_activate(c, cursor);
+                        if let Ok(mut list) = state.try_borrow_mut() {
+                            list.options.on_activate.get_or_insert(on_activate);
+                        }
+                    }
+                    return true;
+                }
+                let visible = Self::visible_rows(node, &list.options);
+                if !list.handle_key(key_event, visible) {
+                    return false;
+                }
+            } else if let Some(mouse_event) = c.event.as_mouse_event() {
+                let visible = Self::visible_rows(node, &list.options);
+                let step = App::WHEEL_SCROLL_STEP as isize;
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => list.scroll(-step, visible),
+                    MouseEventKind::ScrollDown => list.scroll(step, visible),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        // Item nodes are rebuilt, so the list keeps the focus
+                        c.app.emmit(Action::FocusNode(c.self_weak.clone()));
+
+                        let row = mouse_event.row as i16 - node.content_position().1;
+                        if row < 0 {
+                            return false;
+                        }
+                        let index = list.top + row as usize / list.options.item_height as usize;
+                        if index >= list.source.len() {
+                            return false;
+                        }
+
+                        let modifiers = mouse_event.modifiers;
+                        let select = if modifiers.contains(KeyModifiers::CONTROL) {
+                            Select::Toggle
+                        } else if modifiers.contains(KeyModifiers::SHIFT) {
+                            Select::Extend
+                        } else {
+                            Select::Replace
+                        };
+                        list.move_cursor(index, select);
+                        list.scroll_to_cursor(visible);
+                    }
+                    _ => return false,
+                }
+            } else {
+                return false;
+            }
+
+            list.render(node);
+            c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
+            true
+        };
+
+        root.borrow_mut().add_handler(handler, true);
+        (root, handle)
+    }
+
+    /// Returns the number of items fully visible in the list `node`, before the first compute
+    /// the terminal size is used
+    fn visible_rows(node: &Node, options: &ListViewOptions) -> usize {
+        let height = match node.style.clamped_height() {
+            0 => crossterm::terminal::size().map_or(24, |(_, height)| height),
+            height => height,
+        };
+        (height / options.item_height).max(1) as usize
+    }
+}
+
+impl<S: ListSource> ListState<S> {
+    /// Clamps the cursor and the selection to the source length
+    fn clamp(&mut self) {
+        let len = self.source.len();
+        self.cursor = self.cursor.min(len.saturating_sub(1));
+        self.anchor = self.anchor.min(len.saturating_sub(1));
+        self.selected.retain(|&index| index < len);
+        self.top = self.top.min(len.saturating_sub(1));
+
+        if self.options.selection == ListSelection::Single {
+            self.selected = (len > 0).then_some(self.cursor).into_iter().collect();
+        }
+    }
+
+    /// Moves the cursor to `index`, changing the selection by `select`
+    fn move_cursor(&mut self, index: usize, select: Select) {
+        let len = self.source.len();
+        if len == 0 {
+            return;
+        }
+        self.cursor = index.min(len - 1);
+
+        let select = match self.options.selection {
+            ListSelection::Single => Select::Replace,
+            ListSelection::Multiple => select,
+        };
+        match select {
+            Select::Replace => {
+                self.selected = BTreeSet::from([self.cursor]);
+                self.anchor = self.cursor;
+            }
+            Select::Extend => {
+                let (from, to) = (self.anchor.min(self.cursor), self.anchor.max(self.cursor));
+                self.selected = (from..=to).collect();
+            }
+            Select::Toggle => {
+                if !self.selected.remove(&self.cursor) {
+                    self.selected.insert(self.cursor);
+                }
+                self.anchor = self.cursor;
+            }
+            Select::Keep => {}
+        }
+    }
+
+    /// Scrolls the view so the cursor is one of the `visible` items
+    fn scroll_to_cursor(&mut self, visible: usize) {
+        if self.cursor < self.top {
+            self.top = self.cursor;
+        } else if self.cursor >= self.top + visible {
+            self.top = self.cursor + 1 - visible;
+        }
+    }
+
+    /// Scrolls the view by `dy` items without moving the cursor
+    fn scroll(&mut self, dy: isize, visible: usize) {
+        let max = self.source.len().saturating_sub(visible);
+        self.top = self.top.saturating_add_signed(dy).min(max);
+    }
+
+    /// Handles a navigation key, with `visible` items in a page. Returns true if the key was
+    /// handled.
+    fn handle_key(&mut self, key_event: KeyEvent, visible: usize) -> bool {
+        let multiple = self.options.selection == ListSelection::Multiple;
+        let modifiers = key_event.modifiers;
+        let select = if modifiers.contains(KeyModifiers::SHIFT) {
+            Select::Extend
+        } else if modifiers.contains(KeyModifiers::CONTROL) {
+            Select::Keep
+        } else {
+            Select::Replace
+        };
+
+        let last = self.source.len().saturating_sub(1);
+        let page = visible.saturating_sub(1).max(1);
+        let target = match key_event.code {
+            KeyCode::Up => self.cursor.saturating_sub(1),
+            KeyCode::Down => (self.cursor + 1).min(last),
+            KeyCode::PageUp => self.cursor.saturating_sub(page),
+            KeyCode::PageDown => (self.cursor + page).min(last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            KeyCode::Char(' ') if multiple && modifiers.is_empty() => {
+                self.move_cursor(self.cursor, Select::Toggle);
+                return true;
+            }
+            KeyCode::Char('a') if multiple && modifiers == KeyModifiers::CONTROL => {
+                self.selected = (0..self.source.len()).collect();
+                return true;
+            }
+            _ => return false,
+        };
+
+        self.move_cursor(target, select);
+        self.scroll_to_cursor(visible);
+        true
+    }
+
+    /// Rebuilds the item nodes of the list `node` from the first visible item, including one
+    /// partially visible item
+    fn render(&mut self, node: &mut Node) {
+        self.clamp();
+        node.children.clear();
+
+        let rows = ListView::visible_rows(node, &self.options) + 1;
+        let end = self.source.len().min(self.top + rows);
+        for index in self.top..end {
+            let mut item = self.source.render(index);
+            item.style.size = Size::new(
+                SizeValue::percent(100),
+                SizeValue::cells(self.options.item_height),
+            );
+            item.style.flex_shrink = 0;
+
+            if self.selected.contains(&index) {
+                item.style.bg = Some(ListView::SELECTED_COLOR);
+            } else if index == self.cursor {
+                item.style.bg = Some(ListView::CURSOR_COLOR);
+            }
+
+            node.add_child(item.into_handle(), self.root.clone());
+        }
+    }
+}
+
+impl<S: ListSource> ListHandle<S> {
+    /// Returns the source of the list
+    pub fn source(&self) -> Ref<'_, S> {
+        Ref::map(self.0.borrow(), |state| &state.source)
+    }
+
+    /// Returns the source of the list mutably, call [`ListHandle::refresh`] after changing it
+    pub fn source_mut(&self) -> RefMut<'_, S> {
+        RefMut::map(self.0.borrow_mut(), |state| &mut state.source)
+    }
+
+    /// Returns the index of the item under the cursor, `None` if the list is empty
+    pub fn cursor(&self) -> Option<usize> {
+        let state = self.0.borrow();
+        (!state.source.is_empty()).then_some(state.cursor)
+    }
+
+    /// Returns the indices of the selected items, in ascending order
+    pub fn selected(&self) -> Vec<usize> {
+        self.0.borrow().selected.iter().copied().collect()
+    }
+
+    /// Moves the cursor to `index` and selects only that item, the view scrolls to it on the
+    /// next [`ListHandle::refresh`]
+    pub fn select(&self, index: usize) {
+        let mut state = self.0.borrow_mut();
+        state.move_cursor(index, Select::Replace);
+        let visible = match state.root.upgrade() {
+            Some(root) => match root.try_borrow() {
+                Ok(root) => ListView::visible_rows(&root, &state.options),
+                Err(_) => 1,
+            },
+            None => 1,
+        };
+        state.scroll_to_cursor(visible);
+    }
+
+    /// Clears the selection, in [`ListSelection::Single`] mode the item under the cursor stays
+    /// selected
+    pub fn clear_selection(&self) {
+        let mut state = self.0.borrow_mut();
+        if state.options.selection == ListSelection::Multiple {
+            state.selected.clear();
+        }
+    }
+
+    /// Rebuilds the visible items, after the source or the selection has changed. It can't be
+    /// called from an event handler of the list itself.
+    pub fn refresh(&self, app: &AppContext) {
+        let Ok(mut state) = self.0.try_borrow_mut() else {
+            return;
+        };
+        let Some(root) = state.root.upgrade() else {
+            return;
+        };
+        let Ok(mut node) = root.try_borrow_mut() else {
+            return;
+        };
+
+        state.render(&mut node);
+        app.emmit(Action::RecomputeNode(state.root.clone()));
+    }
+}
+
+#[cfg(test)]
+mod list_view_tests {
+    use super::*;
+
+    fn state(len: usize, selection: ListSelection) -> ListState<Vec<String>> {
+        ListState {
+            source: (0..len).map(|i| i.to_string()).collect(),
+            options: ListViewOptions {
+                selection,
+                ..Default::default()
+            },
+            cursor: 0,
+            selected: BTreeSet::new(),
+            anchor: 0,
+            top: 0,
+            root: Node::default().into_handle().weak(),
+        }
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn navigation_scrolls_to_cursor() {
+        let mut list = state(100, ListSelection::Single);
+        let none = KeyModifiers::NONE;
+
+        assert!(list.handle_key(key(KeyCode::PageDown, none), 10));
+        assert_eq!((list.cursor, list.top), (9, 0));
+        assert!(list.handle_key(key(KeyCode::Down, none), 10));
+        assert_eq!((list.cursor, list.top), (10, 1));
+        assert!(list.handle_key(key(KeyCode::End, none), 10));
+        assert_eq!((list.cursor, list.top), (99, 90));
+        assert!(list.handle_key(key(KeyCode::Home, none), 10));
+        assert_eq!((list.cursor, list.top), (0, 0));
+        assert_eq!(list.selected, BTreeSet::from([0]));
+
+        list.scroll(-3, 10);
+        assert_eq!(list.top, 0);
+        list.scroll(200, 10);
+        assert_eq!(list.top, 90);
+        assert!(!list.handle_key(key(KeyCode::Char(' '), none), 10));
+    }
+
+    #[test]
+    fn multiple_selection() {
+        let mut list = state(10, ListSelection::Multiple);
+        let shift = KeyModifiers::SHIFT;
+        let ctrl = KeyModifiers::CONTROL;
+
+        list.move_cursor(2, Select::Replace);
+        list.handle_key(key(KeyCode::Down, shift), 5);
+        list.handle_key(key(KeyCode::Down, shift), 5);
+        assert_eq!(list.selected, BTreeSet::from([2, 3, 4]));
+
+        list.handle_key(key(KeyCode::Down, ctrl), 5);
+        list.handle_key(key(KeyCode::Down, ctrl), 5);
+        list.handle_key(key(KeyCode::Char(' '), KeyModifiers::NONE), 5);
+        list.move_cursor(3, Select::Toggle);
+        assert_eq!(list.selected, BTreeSet::from([2, 4, 6]));
+
+        list.handle_key(key(KeyCode::Char('a'), ctrl), 5);
+        assert_eq!(list.selected.len(), 10);
+
+        list.source.truncate(3);
+        list.clamp();
+        assert_eq!((list.cursor, list.selected.len()), (2, 3));
+    }
+
+    #[test]
+    fn single_selection_follows_cursor() {
+        let (_, handle) = ListView::new(vec!["a", "b", "c"]);
+        assert_eq!(handle.selected(), [0]);
+
+        handle.clear_selection();
+        handle.select(2);
+        assert_eq!(handle.selected(), [2]);
+
+        handle.source_mut().truncate(1);
+        handle.0.borrow_mut().clamp();
+        assert_eq!((handle.cursor(), handle.selected()), (Some(0), vec![0]));
+    }
+
+    #[test]
+    fn renders_visible_items() {
+        let mut list = state(100, ListSelection::Single);
+        let mut node = Node::default();
+        node.style.size = Size::from_cells(10, 5);
+        node.style.max_size = Size::from_cells(10, 5);
+
+        list.render(&mut node);
+        assert_eq!(node.children.len(), 6);
+
+        list.options.item_height = 2;
+        list.top = 98;
+        list.render(&mut node);
+        assert_eq!(node.children.len(), 2);
+        list.top = 0;
+        list.render(&mut node);
+        assert_eq!(node.children.len(), 3);
+    }
+}
@@ -1,15 +1,27 @@
 mod button;
+mod completion;
 pub mod console;
 mod dialog;
 mod drag;
+mod editor;
 mod input;
+mod list_view;
+mod recall;
 mod resizable;
 mod tabs;
+#[cfg(test)]
+mod test_utils;
+mod text_area;
+mod text_view;
 
 pub use button::{Button, MouseClickEvent, on_click_handler};
+pub use completion::{Completer, Completions};
 pub use console::Console;
 pub use dialog::Dialog;
 pub use drag::{Draggable, MouseDragEvent, OnDragResult, on_drag_handler};
-pub use input::Input;
+pub use input::{Input, InputOptions, InputValue};
+pub use list_view::{ListHandle, ListSelection, ListSource, ListView, ListViewOptions};
 pub use resizable::Resizable;
 pub use tabs::Tabs;
+pub use text_area::{TextArea, TextAreaOptions};
+pub use text_view::TextView;
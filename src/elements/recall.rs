@@ -0,0 +1,188 @@
+pub type HistoryProvider = Box<dyn FnMut() -> Vec<String>>;
+
+/// History recall of an [Input](super::Input), navigates the values returned by a
+/// [`HistoryProvider`] and searches them in reverse
+pub(crate) struct Recall {
+    provider: HistoryProvider,
+    /// Entries from the provider, oldest first, fetched when the navigation starts
+    entries: Vec<String>,
+    /// Index of the recalled entry, `None` while the draft is shown
+    index: Option<usize>,
+    /// Value before the navigation started
+    draft: String,
+    search: Option<Search>,
+}
+
+/// Reverse search state of a [`Recall`]
+struct Search {
+    query: String,
+    /// Index of the matching entry
+    index: Option<usize>,
+}
+
+impl Recall {
+    /// Prefix shown before the query while searching
+    pub(crate) const SEARCH_PROMPT: &str = "(reverse search) ";
+
+    /// Creates a new recall for the entries returned by `provider`
+    pub(crate) fn new(provider: HistoryProvider) -> Self {
+        Self {
+            provider,
+            entries: Vec::new(),
+            index: None,
+            draft: String::new(),
+            search: None,
+        }
+    }
+
+    /// Fetches the entries and saves the `current` value, if the navigation hasn't started yet
+    fn start(&mut self, current: &str) {
+        if self.index.is_none() {
+            self.entries = (self.provider)();
+            self.draft = current.to_string();
+        }
+    }
+
+    /// Stops the navigation, the next recall starts from the newest entry
+    pub(crate) fn reset(&mut self) {
+        self.index = None;
+    }
+
+    /// Recalls an older entry if `older` is true, otherwise a newer entry or the draft. Returns
+    /// the value to show, `None` if it doesn't change.
+    pub(crate) fn navigate(&mut self, older: bool, current: &str) -> Option<String> {
+        self.start(current);
+
+        let index = match (self.index, older) {
+            (None, true) => self.entries.len().checked_sub(1)?,
+            (None, false) => return None,
+            (Some(index), true) => index.checked_sub(1)?,
+            (Some(index), false) if index + 1 < self.entries.len() => index + 1,
+            (Some(_), false) => {
+                self.index = None;
+                return Some(self.draft.clone());
+            }
+        };
+
+        self.index = Some(index);
+        Some(self.entries[index].clone())
+    }
+
+    /// True while a reverse search is active
+    pub(crate) fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Starts a reverse search, or searches for an older match if it's active
+    pub(crate) fn search(&mut self, current: &str) {
+        let Some(search) = &self.search else {
+            self.start(current);
+            self.search = Some(Search {
+                query: String::new(),
+                index: None,
+            });
+            return;
+        };
+
+        let from = search.index.unwrap_or(self.entries.len());
+        self.find(from);
+    }
+
+    /// Edits the search query with `edit` and searches it from the newest entry
+    pub(crate) fn edit_query(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(search) = &mut self.search {
+            edit(&mut search.query);
+            search.index = None;
+            self.find(self.entries.len());
+        }
+    }
+
+    /// Finds the newest entry before `from` containing the query, the current match is kept if
+    /// there is none
+    fn find(&mut self, from: usize) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        if search.query.is_empty() {
+            search.index = None;
+            return;
+        }
+
+        let end = from.min(self.entries.len());
+        if let Some(index) = self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.contains(&search.query))
+        {
+            search.index = Some(index);
+        }
+    }
+
+    /// Ends the search. Returns the matching entry if `accept` is true, otherwise the value
+    /// before the search.
+    pub(crate) fn finish_search(&mut self, accept: bool) -> Option<String> {
+        let search = self.search.take()?;
+        let matched = search.index.filter(|_| accept);
+        self.index = matched;
+
+        Some(match matched {
+            Some(index) => self.entries[index].clone(),
+            None => self.draft.clone(),
+        })
+    }
+
+    /// Returns the search query and the matching entry, while searching
+    pub(crate) fn search_state(&self) -> Option<(&str, Option<&str>)> {
+        let search = self.search.as_ref()?;
+        let matched = search.index.map(|index| self.entries[index].as_str());
+        Some((&search.query, matched))
+    }
+}
+
+#[cfg(test)]
+mod recall_tests {
+    use super::*;
+
+    fn recall() -> Recall {
+        Recall::new(Box::new(|| {
+            vec!["first".into(), "second".into(), "third first".into()]
+        }))
+    }
+
+    #[test]
+    fn navigate() {
+        let mut recall = recall();
+        assert_eq!(recall.navigate(false, "draft"), None);
+        assert_eq!(
+            recall.navigate(true, "draft").as_deref(),
+            Some("third first")
+        );
+        assert_eq!(recall.navigate(true, "").as_deref(), Some("second"));
+        assert_eq!(recall.navigate(true, "").as_deref(), Some("first"));
+        assert_eq!(recall.navigate(true, ""), None);
+        assert_eq!(recall.navigate(false, "").as_deref(), Some("second"));
+        assert_eq!(recall.navigate(false, "").as_deref(), Some("third first"));
+        assert_eq!(recall.navigate(false, "").as_deref(), Some("draft"));
+    }
+
+    #[test]
+    fn reverse_search() {
+        let mut recall = recall();
+        recall.search("draft");
+        recall.edit_query(|q| q.push_str("first"));
+        assert_eq!(recall.search_state(), Some(("first", Some("third first"))));
+
+        recall.search("");
+        assert_eq!(recall.search_state(), Some(("first", Some("first"))));
+        // No older match keeps the current one
+        recall.search("");
+        assert_eq!(recall.search_state(), Some(("first", Some("first"))));
+
+        assert_eq!(recall.finish_search(false).as_deref(), Some("draft"));
+        assert!(!recall.is_searching());
+
+        recall.search("draft");
+        recall.edit_query(|q| q.push_str("sec"));
+        assert_eq!(recall.finish_search(true).as_deref(), Some("second"));
+        assert_eq!(recall.navigate(true, "").as_deref(), Some("first"));
+    }
+}
@@ -0,0 +1,44 @@
+//! Helpers shared by the element tests
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{AppContext, Context, Event, Node, NodeHandle};
+
+pub(crate) const NONE: KeyModifiers = KeyModifiers::NONE;
+pub(crate) const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+pub(crate) const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+
+/// Returns a key press event
+pub(crate) fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+/// Dispatches `event` to the handlers of `node` as its target, returns true if it was handled
+pub(crate) fn dispatch(node: &NodeHandle, app: &mut AppContext, event: Event) -> bool {
+    let id = node.borrow().id();
+    let mut c = Context::new(app, id, event, node.weak(), node.weak());
+    let mut node = node.borrow_mut();
+    let captured = node.handle_event(&mut c, true);
+    node.handle_event(&mut c, false) || captured
+}
+
+/// Types `text` into `node` one character at a time, asserting every key is handled
+pub(crate) fn type_text(node: &NodeHandle, app: &mut AppContext, text: &str) {
+    for char in text.chars() {
+        assert!(dispatch(node, app, key(KeyCode::Char(char), NONE)));
+    }
+}
+
+/// Calls `f` with the context of `event` targeting a new root node
+pub(crate) fn with_context<R>(event: Event, f: impl FnOnce(&mut Context) -> R) -> R {
+    let root = Node::default().into_handle();
+    let mut app = AppContext::new(&root, (80, 24));
+    let id = root.borrow().id();
+    f(&mut Context::new(
+        &mut app,
+        id,
+        event,
+        root.weak(),
+        root.weak(),
+    ))
+}
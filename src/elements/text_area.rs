@@ -0,0 +1,560 @@
+use std::ops::Range;
+
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind},
+    style::{Attribute, Color},
+};
+
+use super::{
+    editor::Editor,
+    input::{ChangeHandler, InputValue},
+};
+use crate::{
+    Action, App, Code, Context, Event, Node, NodeHandle, Size, SizeValue,
+    text::{BufferLine, Highlighter, StyleSpan, Text, TextWrap},
+};
+
+/// Options for [`TextArea::with_options`]
+pub struct TextAreaOptions {
+    /// Show a gutter with line numbers on the left
+    pub line_numbers: bool,
+    /// Highlight the background of the line with the cursor
+    pub highlight_current_line: bool,
+    /// Wrap long lines at word boundaries instead of scrolling horizontally, toggled with `Alt+Z`
+    pub soft_wrap: bool,
+    /// `Tab` inserts spaces up to the next multiple of this column. With `0` the key is not
+    /// handled, so it can move the focus
+    pub tab_width: usize,
+    /// Called with the new value after every change
+    pub on_change: Option<ChangeHandler>,
+    /// Syntax highlighter of the content, only the edited lines are highlighted again
+    pub highlighter: Option<Box<dyn Highlighter>>,
+}
+
+impl Default for TextAreaOptions {
+    fn default() -> Self {
+        Self {
+            line_numbers: false,
+            highlight_current_line: false,
+            soft_wrap: false,
+            tab_width: 4,
+            on_change: None,
+            highlighter: None,
+        }
+    }
+}
+
+/// A multi-line text editor, sized to its parent. It has the same line editor as an
+/// [Input](super::Input), and additionally:
+/// - The view scrolls to keep the cursor visible, `PageUp` and `PageDown` move the cursor by a
+///   page and the mouse wheel scrolls the view. Clicking places the cursor.
+/// - `Tab` inserts spaces up to the next tab stop, `Alt+Z` toggles soft wrapping.
+///
+/// The struct itself is used inside the [node's](Node) event handler.
+pub struct TextArea {
+    editor: Editor,
+    options: TextAreaOptions,
+    value: InputValue,
+    /// Lines with the current line and selection styles in the prepared content text
+    styled: Range<usize>,
+}
+
+impl TextArea {
+    const CURRENT_LINE_COLOR: Color = Color::Rgb {
+        r: 40,
+        g: 40,
+        b: 50,
+    };
+
+    /// Creates a new text area [`NodeHandle`] with `content`, and a handle to its value
+    pub fn new(content: &str) -> (NodeHandle, InputValue) {
+        Self::with_options(content, TextAreaOptions::default())
+    }
+
+    /// Creates a new text area [`NodeHandle`] with `content` configured with `options`, and a
+    /// handle to its value
+    pub fn with_options(content: &str, mut options: TextAreaOptions) -> (NodeHandle, InputValue) {
+        let mut editor = Editor::new(content);
+        editor.cursor = (0, 0);
+
+        let value = InputValue::default();
+        value.set(editor.value(), None);
+        let highlighter = options.highlighter.take();
+        let mut area = Self {
+            editor,
+            options,
+            value: value.clone(),
+            styled: 0..0,
+        };
+
+        let mut root = Node::default();
+        root.style.size = Size::new(SizeValue::percent(100), SizeValue::percent(100));
+        root.style.flex_row = true;
+
+        let mut gutter = Node::default();
+        gutter.style.size = Size::new(SizeValue::cells(0), SizeValue::percent(100));
+        gutter.style.dim = true;
+
+        let mut content = Node::default();
+        content.style.size = Size::new(SizeValue::cells(0), SizeValue::percent(100));
+        content.style.flex_grow = 1;
+        if let Some(highlighter) = highlighter {
+            content.text.set_highlighter(highlighter);
+        }
+
+        area.update(&mut content, &mut gutter, true);
+
+        let gutter = gutter.into_handle();
+        let content = content.into_handle();
+        let gutter_weak = gutter.weak();
+        let content_weak = content.weak();
+
+        let handler = move |c: &mut Context, _: &mut Node| {
+            let (Some(content), Some(gutter)) = (content_weak.upgrade(), gutter_weak.upgrade())
+            else {
+                return false;
+            };
+            let (Ok(mut content), Ok(mut gutter)) =
+                (content.try_borrow_mut(), gutter.try_borrow_mut())
+            else {
+                return false;
+            };
+
+            // The cursor is drawn in the focused node
+            if matches!(c.event, Event::NodeFocusGained) && c.target_id != content.id() {
+                c.app.emmit(Action::FocusNode(content_weak.clone()));
+                return false;
+            }
+
+            let before = area.editor.lines.clone();
+            let mut follow_cursor = true;
+
+            if let Some(paste) = c.event.as_paste_event() {
+                area.editor.insert(paste);
+            } else if let Some(key_event) = c.event.as_key_event() {
+                if key_event.kind == KeyEventKind::Release
+                    || !area.handle_key(key_event, &content, c)
+                {
+                    return false;
+                }
+            } else if let Some(mouse_event) = c.event.as_mouse_event() {
+                let step = App::WHEEL_SCROLL_STEP;
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => Self::scroll(&mut content, -step),
+                    MouseEventKind::ScrollDown => Self::scroll(&mut content, step),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if c.target_id != content.id() {
+                            c.app.emmit(Action::FocusNode(content_weak.clone()));
+                            return false;
+                        }
+
+                        let (x, y) = content
+                            .relative_position(mouse_event.column as i16, mouse_event.row as i16);
+                        let select = mouse_event.modifiers.contains(KeyModifiers::SHIFT);
+                        let target = area.position_at(&content, x, y);
+                        area.editor.move_to(target, select);
+                    }
+                    _ => return false,
+                }
+                follow_cursor = !matches!(
+                    mouse_event.kind,
+                    MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                );
+            } else {
+                return false;
+            }
+
+            let changed = before.len() != area.editor.lines.len()
+                || before
+                    .iter()
+                    .zip(&area.editor.lines)
+                    .any(|(a, b)| a.content() != b.content());
+            if changed {
+                let value = area.editor.value();
+                area.value.set(value.clone(), None);
+                if let Some(on_change) = &mut area.options.on_change {
+                    on_change(c, &value);
+                }
+            }
+
+            area.update(&mut content, &mut gutter, follow_cursor);
+            c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
+            true
+        };
+
+        root.add_handler(handler, true);
+        let root = root.into_handle();
+        root.add_child(gutter);
+        root.add_child(content);
+        (root, value)
+    }
+
+    /// Handles a key press, returns true if the key was handled
+    fn handle_key(&mut self, key_event: KeyEvent, content: &Node, c: &mut Context) -> bool {
+        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+        let modifiers = key_event.modifiers - KeyModifiers::SHIFT;
+        let page = Self::visible_size(content).1.saturating_sub(1).max(1) as isize;
+
+        match key_event.code {
+            KeyCode::Tab if key_event.modifiers.is_empty() && self.options.tab_width > 0 => {
+                let (x, y) = self.editor.cursor;
+                let column: usize = self.editor.lines[y]
+                    .grapheme_data()
+                    .iter()
+                    .take(x)
+                    .map(|(.., width)| width)
+                    .sum();
+                let spaces = self.options.tab_width - column % self.options.tab_width;
+                self.editor.insert(&" ".repeat(spaces));
+            }
+            KeyCode::Char('z') if modifiers == KeyModifiers::ALT => {
+                self.options.soft_wrap = !self.options.soft_wrap;
+            }
+            KeyCode::PageUp if modifiers.is_empty() => {
+                let target = self.editor.vertical(self.editor.cursor, -page);
+                self.editor.move_to(target, shift);
+            }
+            KeyCode::PageDown if modifiers.is_empty() => {
+                let target = self.editor.vertical(self.editor.cursor, page);
+                self.editor.move_to(target, shift);
+            }
+            _ => return self.editor.handle_key(key_event, c),
+        }
+
+        true
+    }
+
+    /// Returns the `(width, height)` of the content, before the first compute the terminal size
+    /// is used
+    fn visible_size(content: &Node) -> (u16, u16) {
+        let (terminal_width, terminal_height) = crossterm::terminal::size().unwrap_or((80, 24));
+        let width = match content.style.size.width.computed_size() {
+            0 => terminal_width,
+            width => width,
+        };
+        let height = match content.style.size.height.computed_size() {
+            0 => terminal_height,
+            height => height,
+        };
+
+        (width.max(1), height.max(1))
+    }
+
+    /// Scrolls the content by `dy` rows without moving the cursor
+    fn scroll(content: &mut Node, dy: i32) {
+        let rows = content.text.visual.len();
+        let row = (content.scroll.1 as i64 + dy as i64).clamp(0, rows.saturating_sub(1) as i64);
+        content.scroll.1 = row.min(u16::MAX as i64) as u16;
+    }
+
+    /// Returns the text position `(grapheme, line)` shown at `(x, y)` in the content box
+    fn position_at(&self, content: &Node, x: u16, y: u16) -> (usize, usize) {
+        let visual = &content.text.visual;
+        let row = y as usize + content.scroll.1 as usize;
+        let Some(visual_line) = visual.get(row).or(visual.last()) else {
+            return (0, 0);
+        };
+
+        let line_index = visual_line.line_index.min(self.editor.lines.len() - 1);
+        let line = &self.editor.lines[line_index];
+        // The row ends where the next row of the same line starts
+        let row_end = match visual.get(row + 1) {
+            Some(next) if next.line_index == visual_line.line_index => next.offset,
+            _ => line.count(),
+        };
+
+        let column = x as usize + content.scroll.0 as usize;
+        let mut width = 0;
+        let mut grapheme = visual_line.offset;
+        for (.., grapheme_width) in line.grapheme_data().iter().skip(grapheme) {
+            if grapheme >= row_end || width + grapheme_width > column {
+                break;
+            }
+            width += grapheme_width;
+            grapheme += 1;
+        }
+
+        (grapheme.min(row_end), line_index)
+    }
+
+    /// Updates the content and gutter text, and scrolls the content to the cursor if
+    /// `follow_cursor` is true
+    fn update(&mut self, content: &mut Node, gutter: &mut Node, follow_cursor: bool) {
+        let (x, y) = self.editor.cursor;
+
+        // The text is kept between updates, so only the edited lines are highlighted again
+        let mut text = std::mem::take(&mut content.text);
+        let edit = self.editor.sync_text(&mut text);
+        text.styles.clear();
+        text.wrap = if self.options.soft_wrap {
+            TextWrap::Word
+        } else {
+            TextWrap::None
+        };
+
+        if self.options.highlight_current_line {
+            let length = self.editor.lines[y].count();
+            let code = Code::Background(Self::CURRENT_LINE_COLOR);
+            text.styles.push(StyleSpan::new(code, y, 0, length));
+        }
+        for (line, from, to) in self.editor.selected_spans() {
+            let code = Code::Attribute(Attribute::Reverse);
+            text.styles
+                .push(StyleSpan::new(code, line, from, to - from));
+        }
+
+        // Only the edited lines and the lines whose styles changed are prepared again
+        let lines = text.styles.iter().map(|s| s.line);
+        let styled = match (lines.clone().min(), lines.max()) {
+            (Some(min), Some(max)) => min..max + 1,
+            _ => 0..0,
+        };
+        let mut old_styled = std::mem::replace(&mut self.styled, styled.clone());
+        if let Some((start, removed, inserted)) = edit {
+            text.prepare_edit(start, removed, inserted);
+            old_styled = shift_lines(old_styled, (start, removed, inserted));
+        }
+        for lines in [old_styled, styled] {
+            let lines = lines.start.min(text.input.len())..lines.end.min(text.input.len());
+            text.prepare_edit(lines.start, lines.len(), lines.len());
+        }
+
+        // Wrap now to find the cursor row, the node may not be computed yet
+        let (width, height) = Self::visible_size(content);
+        text.wrap_text(width);
+
+        let row = text
+            .visual
+            .iter()
+            .rposition(|l| l.line_index == y && l.offset <= x)
+            .unwrap_or(y);
+        let offset = text.visual.get(row).map_or(0, |l| l.offset);
+        let column: usize = self.editor.lines[y]
+            .grapheme_data()
+            .iter()
+            .take(x)
+            .skip(offset)
+            .map(|(.., width)| width)
+            .sum();
+        let column = column.min(u16::MAX as usize) as u16;
+        let row = row.min(u16::MAX as usize) as u16;
+        text.cursor = Some((column, row));
+
+        if follow_cursor {
+            let (scroll_x, scroll_y) = &mut content.scroll;
+            if row < *scroll_y {
+                *scroll_y = row;
+            } else if row >= *scroll_y + height {
+                *scroll_y = row + 1 - height;
+            }
+
+            if self.options.soft_wrap {
+                *scroll_x = 0;
+            } else if column < *scroll_x {
+                *scroll_x = column;
+            } else if column >= *scroll_x + width {
+                *scroll_x = column + 1 - width;
+            }
+        }
+        let max_row = text.visual.len().saturating_sub(1).min(u16::MAX as usize) as u16;
+        content.scroll.1 = content.scroll.1.min(max_row);
+
+        self.update_gutter(&text, gutter, content.scroll.1, height);
+        content.text = text;
+    }
+
+    /// Sets the gutter text to the line numbers of the `height` visual rows of `text` from the
+    /// `scroll` row
+    fn update_gutter(&self, text: &Text, gutter: &mut Node, scroll: u16, height: u16) {
+        if !self.options.line_numbers {
+            gutter.style.size.width = SizeValue::cells(0);
+            gutter.text = Text::default();
+            return;
+        }
+
+        let digits = self.editor.lines.len().to_string().len();
+        gutter.style.size.width = SizeValue::cells(digits as u16 + 1);
+
+        let current = self.editor.cursor.1;
+        let mut lines = Vec::with_capacity(height as usize);
+        let mut styles = Vec::new();
+        let rows = text
+            .visual
+            .iter()
+            .skip(scroll as usize)
+            .take(height as usize);
+        for (row, visual_line) in rows.enumerate() {
+            let number = if visual_line.offset == 0 {
+                (visual_line.line_index + 1).to_string()
+            } else {
+                String::new()
+            };
+            lines.push(BufferLine::new(format!("{number:>digits$} ")));
+
+            if visual_line.line_index == current && visual_line.offset == 0 {
+                let code = Code::Attribute(Attribute::NormalIntensity);
+                styles.push(StyleSpan::new(code, row, 0, digits));
+            }
+        }
+
+        let mut gutter_text = Text::new_from(lines);
+        gutter_text.wrap = TextWrap::None;
+        gutter_text.styles = styles;
+        gutter_text.prepare_lines(0..gutter_text.input.len());
+        gutter.text = gutter_text;
+    }
+}
+
+/// Returns the `lines` after `removed` lines starting at `start` have been replaced by `inserted`
+/// lines. Replaced lines map to the inserted ones.
+fn shift_lines(
+    lines: Range<usize>,
+    (start, removed, inserted): (usize, usize, usize),
+) -> Range<usize> {
+    let shift = |line: usize, replaced: usize| match line {
+        line if line < start => line,
+        line if line >= start + removed => line + inserted - removed,
+        _ => replaced,
+    };
+    shift(lines.start, start)..shift(lines.end, start + inserted)
+}
+
+#[cfg(test)]
+mod text_area_tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        AppContext,
+        elements::test_utils::{NONE, dispatch, key},
+    };
+
+    /// Returns the text area with a `width` by `height` content box, and its gutter and content
+    fn text_area(
+        content: &str,
+        options: TextAreaOptions,
+        (width, height): (u16, u16),
+    ) -> (NodeHandle, InputValue, AppContext) {
+        let (root, value) = TextArea::with_options(content, options);
+        root.borrow().children[1].borrow_mut().style.size = Size::from_cells(width, height);
+        let app = AppContext::new(&root, (80, 24));
+        (root, value, app)
+    }
+
+    fn lines(node: &Node) -> Vec<String> {
+        node.text
+            .input
+            .iter()
+            .map(|l| l.content().to_string())
+            .collect()
+    }
+
+    fn child(root: &NodeHandle, index: usize) -> Rc<RefCell<Node>> {
+        root.borrow().children[index].inner().clone()
+    }
+
+    #[test]
+    fn gutter() {
+        let content = (1..=12)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let options = TextAreaOptions {
+            line_numbers: true,
+            ..Default::default()
+        };
+        let (root, _, mut app) = text_area(&content, options, (10, 3));
+
+        for _ in 0..5 {
+            assert!(dispatch(&root, &mut app, key(KeyCode::Down, NONE)));
+        }
+        let (gutter, content) = (child(&root, 0), child(&root, 1));
+        assert_eq!(gutter.borrow().style.size.width.computed_size(), 3);
+
+        // The gutter shows the visible rows of the scrolled content
+        assert_eq!(content.borrow().scroll.1, 3);
+        assert_eq!(lines(&gutter.borrow()), [" 4 ", " 5 ", " 6 "]);
+    }
+
+    #[test]
+    fn soft_wrap() {
+        let options = TextAreaOptions {
+            line_numbers: true,
+            soft_wrap: true,
+            ..Default::default()
+        };
+        let (root, _, mut app) = text_area("hello world foo\nbar", options, (10, 3));
+        let (gutter, content) = (child(&root, 0), child(&root, 1));
+
+        dispatch(&root, &mut app, key(KeyCode::End, NONE));
+        assert_eq!(lines(&gutter.borrow()), ["1 ", "  ", "2 "]);
+        assert_eq!(content.borrow().text.cursor, Some((9, 1)));
+
+        // Without wrapping the view scrolls horizontally to the cursor
+        assert!(dispatch(
+            &root,
+            &mut app,
+            key(KeyCode::Char('z'), KeyModifiers::ALT)
+        ));
+        assert_eq!(lines(&gutter.borrow()), ["1 ", "2 "]);
+        assert_eq!(content.borrow().text.cursor, Some((15, 0)));
+        assert_eq!(content.borrow().scroll, (6, 0));
+    }
+
+    #[test]
+    fn tab_width() {
+        let (root, value, mut app) = text_area("", TextAreaOptions::default(), (10, 3));
+        dispatch(&root, &mut app, key(KeyCode::Tab, NONE));
+        dispatch(&root, &mut app, key(KeyCode::Char('a'), NONE));
+        dispatch(&root, &mut app, key(KeyCode::Tab, NONE));
+        assert_eq!(value.value(), "    a   ");
+
+        // Tab is not handled, so it can move the focus
+        let options = TextAreaOptions {
+            tab_width: 0,
+            ..Default::default()
+        };
+        let (root, value, mut app) = text_area("", options, (10, 3));
+        assert!(!dispatch(&root, &mut app, key(KeyCode::Tab, NONE)));
+        assert_eq!(value.value(), "");
+    }
+
+    #[test]
+    fn edits_keep_prepared_lines() {
+        let options = TextAreaOptions {
+            highlight_current_line: true,
+            soft_wrap: true,
+            ..Default::default()
+        };
+        let (root, _, mut app) = text_area("one two three\nfour\nfive six", options, (6, 3));
+        let content = child(&root, 1);
+
+        let keys = [
+            key(KeyCode::Down, NONE),
+            key(KeyCode::Char('x'), NONE),
+            key(KeyCode::Enter, NONE),
+            key(KeyCode::Down, KeyModifiers::SHIFT),
+            key(KeyCode::Backspace, NONE),
+            key(KeyCode::Up, NONE),
+        ];
+        for event in keys {
+            dispatch(&root, &mut app, event);
+
+            // The kept lines are the same as if the whole text was prepared again
+            let content = content.borrow();
+            let mut text = Text::new_from(content.text.input.clone());
+            text.wrap = content.text.wrap;
+            text.styles = content.text.styles.clone();
+            text.prepare_lines(0..text.input.len());
+            text.wrap_text(6);
+
+            let rows = |text: &Text| {
+                let visual = text.visual.iter();
+                visual
+                    .map(|l| (l.line_index, l.offset, l.content.clone()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(rows(&content.text), rows(&text));
+        }
+    }
+}
@@ -0,0 +1,929 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyModifiers, MouseEventKind},
+    style::Color,
+};
+
+use crate::{
+    Action, App, Code, Context, Message, Node, NodeHandle, NodeId, Size, SizeValue, WorkerContext,
+    text::{BufferLine, StyleSpan, Text, TextWrap},
+};
+
+/// A read-only view for very large texts, like log files. Only the visible lines and a margin
+/// around them are prepared and wrapped. Files are read on demand, their lines are indexed in a
+/// worker, so lines appear while the rest of the file is indexed. The status line shows the
+/// progress, and the view keeps following the end of the file after `End`.
+///
+/// The view scrolls with the arrow keys, `PageUp`, `PageDown`, `Home`, `End` and the mouse
+/// wheel. `/` starts an incremental search of the lines around the view, `Enter` confirms it and
+/// searches the whole text, `Esc` cancels it. `n` and `N` jump to the next and previous match in
+/// the whole text. Matches in view are highlighted.
+///
+/// The struct itself is used inside the [node's](Node) event handler.
+pub struct TextView {
+    source: Source,
+    /// Range of source lines prepared in the content node
+    window: Range<usize>,
+    wrap: TextWrap,
+    search: Search,
+    /// True if the view moves to the last line as more lines are indexed
+    follow_end: bool,
+}
+
+/// Position of a query match, `(line, byte)` with the byte index in the line
+type Position = (usize, usize);
+
+#[derive(Debug, Default)]
+/// Incremental search state of a [`TextView`]
+struct Search {
+    query: String,
+    /// True while the query is being typed
+    typing: bool,
+    /// Position of the current match
+    current: Option<Position>,
+    /// Line from which the typed query is searched
+    origin: usize,
+}
+
+/// Lines shown by a [`TextView`]
+enum Source {
+    /// Text in memory, with the byte index of each line start
+    Memory {
+        text: String,
+        line_starts: Vec<usize>,
+    },
+    /// File read on demand, its lines are indexed by a worker
+    File {
+        file: File,
+        index: Arc<Mutex<LineIndex>>,
+    },
+}
+
+#[derive(Debug, Default)]
+/// Line index of a file, built in chunks
+struct LineIndex {
+    /// Byte index of each line start
+    starts: Vec<u64>,
+    /// Number of bytes indexed so far
+    indexed: u64,
+    /// True when the whole file is indexed
+    done: bool,
+}
+
+impl LineIndex {
+    /// Size of the chunks the file is indexed in
+    const CHUNK_SIZE: usize = 1 << 20;
+
+    /// Indexes the next `chunk` of the file, an empty chunk marks the end of the file
+    fn add_chunk(&mut self, chunk: &[u8]) {
+        if self.starts.is_empty() {
+            self.starts.push(0);
+        }
+        if chunk.is_empty() {
+            self.done = true;
+            return;
+        }
+
+        let offset = self.indexed;
+        let starts = chunk.iter().enumerate().filter(|&(_, &byte)| byte == b'\n');
+        self.starts
+            .extend(starts.map(|(i, _)| offset + i as u64 + 1));
+        self.indexed += chunk.len() as u64;
+    }
+
+    /// Reads and indexes the next chunk of `file`, returns true when the whole file is indexed
+    fn index_next(
+        index: &Mutex<LineIndex>,
+        file: &mut File,
+        buffer: &mut [u8],
+    ) -> io::Result<bool> {
+        let read = file.read(buffer)?;
+        let mut index = index.lock().unwrap();
+        index.add_chunk(&buffer[..read]);
+        Ok(index.done)
+    }
+
+    /// Number of complete lines, the last line is complete when the whole file is indexed
+    fn line_count(&self) -> usize {
+        match self.done {
+            true => self.starts.len(),
+            false => self.starts.len().saturating_sub(1),
+        }
+    }
+
+    /// Returns the byte range of the line at `index`, without the line break
+    fn line_range(&self, index: usize) -> Range<u64> {
+        let start = self.starts[index];
+        let end = self
+            .starts
+            .get(index + 1)
+            .map_or(self.indexed, |next| next - 1);
+        start..end
+    }
+}
+
+impl Source {
+    /// Number of lines read at once while searching
+    const BLOCK_LINES: usize = 1024;
+
+    /// Creates a source of the `text` in memory
+    fn memory(text: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self::Memory { text, line_starts }
+    }
+
+    /// Opens the file at `path`, and indexes its first chunk
+    fn file(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let index = Mutex::new(LineIndex::default());
+        let mut buffer = vec![0; LineIndex::CHUNK_SIZE];
+        while index.lock().unwrap().indexed < LineIndex::CHUNK_SIZE as u64 {
+            if LineIndex::index_next(&index, &mut file, &mut buffer)? {
+                break;
+            }
+        }
+
+        Ok(Self::File {
+            file,
+            index: Arc::new(index),
+        })
+    }
+
+    /// True if all lines are available
+    fn is_indexed(&self) -> bool {
+        match self {
+            Self::Memory { .. } => true,
+            Self::File { index, .. } => index.lock().unwrap().done,
+        }
+    }
+
+    /// Number of lines available
+    fn line_count(&self) -> usize {
+        match self {
+            Self::Memory { line_starts, .. } => line_starts.len(),
+            Self::File { index, .. } => index.lock().unwrap().line_count(),
+        }
+    }
+
+    /// Returns the lines in `range` without line endings, invalid UTF-8 in files is replaced
+    /// with `U+FFFD`
+    fn lines(&self, range: Range<usize>) -> Vec<String> {
+        let range = range.start..range.end.min(self.line_count());
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        let strip = |line: &str| line.strip_suffix('\r').unwrap_or(line).to_string();
+        match self {
+            Self::Memory { text, line_starts } => range
+                .map(|i| {
+                    let start = line_starts[i];
+                    let end = line_starts.get(i + 1).map_or(text.len(), |next| next - 1);
+                    strip(&text[start..end])
+                })
+                .collect(),
+            Self::File { file, index } => {
+                let ranges = {
+                    let index = index.lock().unwrap();
+                    range.map(|i| index.line_range(i)).collect::<Vec<_>>()
+                };
+                let start = ranges[0].start;
+                let end = ranges[ranges.len() - 1].end;
+
+                let mut bytes = Vec::new();
+                let read = (&*file)
+                    .seek(SeekFrom::Start(start))
+                    .and_then(|_| (&*file).take(end - start).read_to_end(&mut bytes));
+                if let Err(_err) = read {
+                    warn!("Could not read lines of a text view: {_err}");
+                }
+
+                ranges
+                    .iter()
+                    .map(|range| {
+                        let from = ((range.start - start) as usize).min(bytes.len());
+                        let to = ((range.end - start) as usize).min(bytes.len());
+                        strip(&String::from_utf8_lossy(&bytes[from..to]))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the first position found by `find` in the lines of `range`, from the last line
+    /// if `reverse` is true. `find` returns the byte index of a match in a line.
+    fn find_in(
+        &self,
+        range: Range<usize>,
+        reverse: bool,
+        mut find: impl FnMut(usize, &str) -> Option<usize>,
+    ) -> Option<Position> {
+        let blocks = range
+            .clone()
+            .step_by(Self::BLOCK_LINES)
+            .map(|start| start..(start + Self::BLOCK_LINES).min(range.end));
+        let blocks: Box<dyn Iterator<Item = Range<usize>>> = match reverse {
+            true => Box::new(blocks.rev()),
+            false => Box::new(blocks),
+        };
+
+        for block in blocks {
+            let lines = self.lines(block.clone());
+            let mut lines = block.zip(lines);
+            let found = match reverse {
+                true => lines.rev().find_map(|(i, line)| Some((i, find(i, &line)?))),
+                false => lines.find_map(|(i, line)| Some((i, find(i, &line)?))),
+            };
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+impl TextView {
+    const MATCH_COLOR: Color = Color::Rgb {
+        r: 120,
+        g: 120,
+        b: 40,
+    };
+    const CURRENT_MATCH_COLOR: Color = Color::Rgb {
+        r: 230,
+        g: 180,
+        b: 40,
+    };
+
+    /// Creates a new text view [`NodeHandle`] with the `content` text, sized to its parent
+    pub fn new(content: impl Into<String>, wrap: TextWrap) -> NodeHandle {
+        Self::with_source(Source::memory(content.into()), wrap, None)
+    }
+
+    /// Creates a new text view [`NodeHandle`] with the text of the file at `path`. Invalid UTF-8
+    /// is replaced with `U+FFFD`. Only the first chunk of the file is indexed before returning,
+    /// the rest is indexed in a worker.
+    pub fn file(path: impl AsRef<Path>, wrap: TextWrap) -> io::Result<NodeHandle> {
+        let path = path.as_ref();
+        let source = Source::file(path)?;
+        Ok(Self::with_source(source, wrap, Some(path.to_path_buf())))
+    }
+
+    /// Creates the text view state showing `source`
+    fn from_source(source: Source, wrap: TextWrap) -> Self {
+        Self {
+            source,
+            window: 0..0,
+            wrap,
+            search: Search::default(),
+            follow_end: false,
+        }
+    }
+
+    /// Creates a new text view [`NodeHandle`] showing `source`, the rest of the file at `path` is
+    /// indexed in a worker
+    fn with_source(source: Source, wrap: TextWrap, path: Option<PathBuf>) -> NodeHandle {
+        let mut view = Self::from_source(source, wrap);
+        let is_indexed = view.source.is_indexed();
+
+        let mut root = Node::default();
+        root.style.size = Size::new(SizeValue::percent(100), SizeValue::percent(100));
+
+        let mut content = Node::default();
+        content.style.size = Size::new(SizeValue::percent(100), SizeValue::cells(0));
+        content.style.flex_grow = 1;
+        view.prepare(&mut content, 0, 0);
+        let mut status = Node::default();
+        status.style.size = Size::new(SizeValue::percent(100), SizeValue::cells(0));
+        view.update_status(&mut status);
+
+        let view = Arc::new(Mutex::new(view));
+        if let Some(path) = path
+            && !is_indexed
+        {
+            Self::start_indexing(&view, path, &mut content, status.id());
+        }
+
+        let content = content.into_handle();
+        let status = status.into_handle();
+        let content_weak = content.weak();
+        let status_weak = status.weak();
+
+        let handler = move |c: &mut Context, _: &mut Node| {
+            let Some(content) = content_weak.upgrade() else {
+                return false;
+            };
+            let (Ok(mut content), Ok(mut view)) = (content.try_borrow_mut(), view.lock()) else {
+                return false;
+            };
+            let view = &mut *view;
+
+            let handled = if let Some(key_event) = c.event.as_key_press_event() {
+                view.handle_key(key_event, &mut content)
+            } else if let Some(mouse_event) = c.event.as_mouse_event() {
+                let step = App::WHEEL_SCROLL_STEP;
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => view.scroll(&mut content, -step),
+                    MouseEventKind::ScrollDown => view.scroll(&mut content, step),
+                    MouseEventKind::ScrollLeft => view.scroll_horizontal(&mut content, -step),
+                    MouseEventKind::ScrollRight => view.scroll_horizontal(&mut content, step),
+                    _ => return false,
+                }
+                true
+            } else if let Some(paste) = c.event.as_paste_event() {
+                if !view.search.typing {
+                    return false;
+                }
+                view.search
+                    .query
+                    .push_str(paste.lines().next().unwrap_or_default());
+                view.search_typed(&mut content);
+                true
+            } else {
+                false
+            };
+
+            if handled {
+                if let Some(status) = status_weak.upgrade() {
+                    view.update_status(&mut status.borrow_mut());
+                }
+                c.app.emmit(Action::RecomputeNode(c.self_weak.clone()));
+            }
+            handled
+        };
+
+        root.add_handler(handler, true);
+        let root = root.into_handle();
+        root.add_child(content);
+        root.add_child(status);
+        root
+    }
+
+    /// Minimum time between the updates of the view while the file is indexed
+    const INDEXING_UPDATE: Duration = Duration::from_millis(100);
+
+    /// Starts the worker indexing the rest of the file at `path` in the `content` node. The view
+    /// and its `status` node are updated as lines are indexed.
+    fn start_indexing(view: &Arc<Mutex<Self>>, path: PathBuf, content: &mut Node, status: NodeId) {
+        let Source::File { index, .. } = &view.lock().unwrap().source else {
+            return;
+        };
+        let index = Arc::clone(index);
+        let offset = index.lock().unwrap().indexed;
+        let view = Arc::clone(view);
+
+        content.start_worker(move |c| {
+            let indexed = File::open(&path).and_then(|mut file| {
+                file.seek(SeekFrom::Start(offset))?;
+                let mut buffer = vec![0; LineIndex::CHUNK_SIZE];
+                let mut updated = Instant::now();
+                while !c.is_shutdown() {
+                    let done = LineIndex::index_next(&index, &mut file, &mut buffer)?;
+                    if done || updated.elapsed() >= Self::INDEXING_UPDATE {
+                        updated = Instant::now();
+                        Self::send_indexed(&c, &view, status);
+                    }
+                    if done {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+
+            if let Err(_err) = indexed {
+                warn!("Could not index {path:?}: {_err}");
+                // Show the lines indexed so far
+                index.lock().unwrap().done = true;
+                Self::send_indexed(&c, &view, status);
+            }
+        });
+    }
+
+    /// Sends a message showing the lines indexed so far in the view and its `status` node
+    fn send_indexed(c: &WorkerContext, view: &Arc<Mutex<Self>>, status: NodeId) {
+        let view = Arc::clone(view);
+        let message = Message::exec(move |mut c| {
+            let Ok(mut view) = view.lock() else {
+                return;
+            };
+            view.show_indexed(&mut c.node_mut());
+            if let Some(status) = c.find_node(status).and_then(|s| s.upgrade()) {
+                view.update_status(&mut status.borrow_mut());
+            }
+
+            // The status height may have changed
+            if let Some(root) = c.node().parent.clone() {
+                c.app().emmit(Action::RecomputeNode(root));
+            }
+        });
+        c.send(message).ok();
+    }
+
+    /// Number of lines prepared before and after the visible lines, the terminal height
+    fn margin() -> usize {
+        match crossterm::terminal::size() {
+            Ok((_, height)) if height > 0 => height as usize,
+            _ => 50,
+        }
+    }
+
+    /// Returns the source line and its wrapped row at the top of the view
+    fn position(&self, content: &Node) -> (usize, usize) {
+        let visual = &content.text.visual;
+        let Some(last) = visual.len().checked_sub(1) else {
+            return (self.window.start, 0);
+        };
+
+        let row = (content.scroll.1 as usize).min(last);
+        let line_index = visual[row].line_index;
+        let line_row = visual[..row]
+            .iter()
+            .rev()
+            .take_while(|l| l.line_index == line_index)
+            .count();
+
+        (self.window.start + line_index, line_row)
+    }
+
+    /// Prepares the window of lines around `line`, and scrolls the content to `row` of `line`
+    fn prepare(&mut self, content: &mut Node, line: usize, row: usize) {
+        self.follow_end = false;
+        let margin = Self::margin();
+        let start = line.saturating_sub(margin);
+        let end = line
+            .saturating_add(2 * margin)
+            .min(self.source.line_count());
+        self.window = start..end;
+
+        let input = self
+            .source
+            .lines(self.window.clone())
+            .into_iter()
+            .map(BufferLine::new)
+            .collect();
+        let mut text = Text::new_from(input);
+        text.wrap = self.wrap;
+        text.styles = self.highlights(&text.input);
+        text.prepare_lines(0..text.input.len());
+
+        // Wrap now to find the row, the node is not computed yet
+        let width = match content.style.size.width.computed_size() {
+            0 => match crossterm::terminal::size() {
+                Ok((width, _)) if width > 0 => width,
+                _ => 80,
+            },
+            width => width,
+        };
+        text.wrap_text(width);
+
+        let line_row = text
+            .visual
+            .iter()
+            .position(|l| l.line_index == line - start)
+            .unwrap_or(0);
+        content.scroll.1 = (line_row + row).min(u16::MAX as usize) as u16;
+        content.text = text;
+    }
+
+    /// Prepares the window again at the current position
+    fn refresh(&mut self, content: &mut Node) {
+        let (line, row) = self.position(content);
+        self.prepare(content, line, row);
+    }
+
+    /// Prepares the window at the last line, and scrolls to the last page. The view follows the
+    /// end while the file is indexed.
+    fn prepare_end(&mut self, content: &mut Node) {
+        let last = self.source.line_count().saturating_sub(1);
+        self.prepare(content, last, 0);
+        // Clamped to the last page when computed
+        content.scroll.1 = content.text.visual.len().min(u16::MAX as usize) as u16;
+        self.follow_end = !self.source.is_indexed();
+    }
+
+    /// Shows the lines indexed since the window was prepared, if the window ends before them or
+    /// the view follows the end
+    fn show_indexed(&mut self, content: &mut Node) {
+        if self.follow_end {
+            self.prepare_end(content);
+            return;
+        }
+
+        let (line, _) = self.position(content);
+        let end = line
+            .saturating_add(2 * Self::margin())
+            .min(self.source.line_count());
+        if end > self.window.end {
+            self.refresh(content);
+        }
+    }
+
+    /// Scrolls the content by `dy` wrapped rows, the window moves when the top row gets close to
+    /// its edges
+    fn scroll(&mut self, content: &mut Node, dy: i32) {
+        self.follow_end = false;
+        let rows = content.text.visual.len();
+        let row = (content.scroll.1 as i64 + dy as i64).clamp(0, rows.saturating_sub(1) as i64);
+        content.scroll.1 = row.min(u16::MAX as i64) as u16;
+
+        let margin = Self::margin();
+        let row = row as usize;
+        let near_start = row < margin && self.window.start > 0;
+        let near_end = row + 2 * margin > rows && self.window.end < self.source.line_count();
+
+        if near_start || near_end {
+            self.refresh(content);
+        }
+    }
+
+    /// Scrolls unwrapped content by `dx` columns
+    fn scroll_horizontal(&mut self, content: &mut Node, dx: i32) {
+        if self.wrap != TextWrap::None {
+            return;
+        }
+
+        let max = content.max_scroll().0;
+        content.scroll.0 = (content.scroll.0 as i32 + dx).clamp(0, max as i32) as u16;
+    }
+
+    /// Returns the number of rows visible in the content
+    fn page_rows(content: &Node) -> i32 {
+        match content.style.size.height.computed_size() {
+            0 => Self::margin() as i32,
+            height => height as i32,
+        }
+    }
+
+    /// Handles a key press, returns true if it was handled
+    fn handle_key(&mut self, key_event: KeyEvent, content: &mut Node) -> bool {
+        let modifiers = key_event.modifiers - KeyModifiers::SHIFT;
+        if !modifiers.is_empty() {
+            return false;
+        }
+
+        if self.search.typing {
+            match key_event.code {
+                KeyCode::Char(char) => {
+                    self.search.query.push(char);
+                    self.search_typed(content);
+                    return true;
+                }
+                KeyCode::Backspace => {
+                    self.search.query.pop();
+                    self.search_typed(content);
+                    return true;
+                }
+                KeyCode::Enter => {
+                    self.search.typing = false;
+                    let lines = 0..self.source.line_count();
+                    self.search_from(content, (self.search.origin, 0), true, lines);
+                    return true;
+                }
+                KeyCode::Esc => {
+                    let line = self.search.origin;
+                    self.search = Search::default();
+                    self.prepare(content, line, 0);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        let page = Self::page_rows(content).saturating_sub(1).max(1);
+        match key_event.code {
+            KeyCode::Up => self.scroll(content, -1),
+            KeyCode::Down => self.scroll(content, 1),
+            KeyCode::PageUp => self.scroll(content, -page),
+            KeyCode::PageDown => self.scroll(content, page),
+            KeyCode::Left => self.scroll_horizontal(content, -1),
+            KeyCode::Right => self.scroll_horizontal(content, 1),
+            KeyCode::Home => self.prepare(content, 0, 0),
+            KeyCode::End => self.prepare_end(content),
+            KeyCode::Char('/') => {
+                let (line, _) = self.position(content);
+                self.search = Search {
+                    typing: true,
+                    origin: line,
+                    ..Default::default()
+                };
+                self.refresh(content);
+            }
+            KeyCode::Char('n') if !self.search.query.is_empty() => {
+                let from = match self.search.current {
+                    Some((line, byte)) => (line, byte + self.search.query.len()),
+                    None => (self.position(content).0, 0),
+                };
+                let lines = 0..self.source.line_count();
+                self.search_from(content, from, true, lines);
+            }
+            KeyCode::Char('N') if !self.search.query.is_empty() => {
+                let from = self
+                    .search
+                    .current
+                    .unwrap_or_else(|| (self.position(content).0, 0));
+                let lines = 0..self.source.line_count();
+                self.search_from(content, from, false, lines);
+            }
+            KeyCode::Esc if !self.search.query.is_empty() => {
+                self.search = Search::default();
+                self.refresh(content);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Returns the closest query match from the `from` position in the source `lines`, wrapping
+    /// around them. Lines are searched in blocks, so it stops reading at the first match.
+    fn find(&self, (line, byte): Position, forward: bool, lines: Range<usize>) -> Option<Position> {
+        let query = self.search.query.as_str();
+        let (start, end) = (lines.start, lines.end.min(self.source.line_count()));
+        if query.is_empty() || start >= end {
+            return None;
+        }
+        let (line, byte) = match line {
+            line if line < start => (start, 0),
+            line if line >= end => (end - 1, usize::MAX),
+            line => (line, byte),
+        };
+
+        if forward {
+            let after = |i: usize, l: &str| match i == line {
+                true => l.get(byte..)?.find(query).map(|b| byte + b),
+                false => l.find(query),
+            };
+            self.source.find_in(line..end, false, after).or_else(|| {
+                self.source
+                    .find_in(start..line + 1, false, |_, l| l.find(query))
+            })
+        } else {
+            let before = |i: usize, l: &str| match i == line {
+                true => l.get(..byte)?.rfind(query),
+                false => l.rfind(query),
+            };
+            self.source
+                .find_in(start..line + 1, true, before)
+                .or_else(|| self.source.find_in(line..end, true, |_, l| l.rfind(query)))
+        }
+    }
+
+    /// Searches the typed query from the search origin. Only the prepared window is searched,
+    /// so typing never reads the whole source, `Enter` searches all lines.
+    fn search_typed(&mut self, content: &mut Node) {
+        let from = (self.search.origin, 0);
+        self.search_from(content, from, true, self.window.clone());
+    }
+
+    /// Searches the query in the source `lines` from the `from` position, and scrolls to the
+    /// match if it is not visible
+    fn search_from(
+        &mut self,
+        content: &mut Node,
+        from: Position,
+        forward: bool,
+        lines: Range<usize>,
+    ) {
+        self.search.current = self.find(from, forward, lines);
+
+        let Some((line, _)) = self.search.current else {
+            self.refresh(content);
+            return;
+        };
+
+        // Keep the position if the matched line is visible
+        let top = content.scroll.1 as usize;
+        let height = Self::page_rows(content) as usize;
+        let visible = line
+            .checked_sub(self.window.start)
+            .is_some_and(|line_index| {
+                content
+                    .text
+                    .visual
+                    .iter()
+                    .skip(top)
+                    .take(height)
+                    .any(|l| l.line_index == line_index)
+            });
+
+        if visible {
+            self.refresh(content);
+        } else {
+            self.prepare(content, line, 0);
+        }
+    }
+
+    /// Returns the highlight styles of the query matches in the window `lines`
+    fn highlights(&self, lines: &[BufferLine]) -> Vec<StyleSpan> {
+        let query = self.search.query.as_str();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut styles = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let line_index = self.window.start + i;
+
+            for (byte, _) in line.content().match_indices(query) {
+                let end = byte + query.len();
+                let Some(start) = line.byte_to_grapheme_index(byte) else {
+                    continue;
+                };
+                let end = match end == line.content().len() {
+                    true => line.count(),
+                    false => match line.byte_to_grapheme_index(end) {
+                        Some(end) => end,
+                        None => continue,
+                    },
+                };
+
+                let color = match self.search.current == Some((line_index, byte)) {
+                    true => Self::CURRENT_MATCH_COLOR,
+                    false => Self::MATCH_COLOR,
+                };
+                let length = end - start;
+                styles.push(StyleSpan::new(Code::Background(color), i, start, length));
+                styles.push(StyleSpan::new(
+                    Code::Foreground(Color::Black),
+                    i,
+                    start,
+                    length,
+                ));
+            }
+        }
+
+        styles
+    }
+
+    /// Shows the search prompt in the `status` node while searching or if nothing was found, and
+    /// the indexing progress otherwise
+    fn update_status(&self, status: &mut Node) {
+        let Search { query, typing, .. } = &self.search;
+        let not_found = !query.is_empty() && self.search.current.is_none();
+
+        let text = match (typing, not_found) {
+            (true, true) => format!("/{query} (not found nearby, Enter searches all lines)"),
+            (true, false) => format!("/{query}"),
+            (false, true) => format!("Pattern not found: {query}"),
+            (false, false) if !self.source.is_indexed() => {
+                format!("Indexing… {} lines", self.source.line_count())
+            }
+            (false, false) => String::new(),
+        };
+
+        let height = !text.is_empty() as u16;
+        status.style.size.height = SizeValue::cells(height);
+        status.text = text.into();
+    }
+}
+
+#[cfg(test)]
+mod text_view_tests {
+    use super::*;
+
+    fn key(view: &mut TextView, content: &mut Node, code: KeyCode) -> bool {
+        view.handle_key(KeyEvent::new(code, KeyModifiers::NONE), content)
+    }
+
+    #[test]
+    fn file_lines() {
+        let mut index = LineIndex::default();
+        index.add_chunk(b"ab\nc");
+        index.add_chunk(b"d\n");
+        assert_eq!(
+            (index.starts.as_slice(), index.line_count()),
+            (&[0, 3, 6][..], 2)
+        );
+        index.add_chunk(b"");
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_range(1), 3..5);
+
+        let path = std::env::temp_dir().join(format!("tui_text_view_{}", std::process::id()));
+        std::fs::write(&path, b"one\r\ntw\xffo\n\nlast").unwrap();
+        let source = Source::file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.lines(0..10), ["one", "tw\u{FFFD}o", "", "last"]);
+        assert_eq!(source.lines(1..3), ["tw\u{FFFD}o", ""]);
+    }
+
+    #[test]
+    fn window_paging() {
+        let text = (0..1000).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        let mut view = TextView::from_source(Source::memory(text.join("\n")), TextWrap::None);
+        let mut content = Node::default();
+        view.prepare(&mut content, 0, 0);
+        let margin = TextView::margin();
+        assert_eq!(view.window, 0..2 * margin);
+
+        key(&mut view, &mut content, KeyCode::PageDown);
+        let line = margin - 1;
+        assert_eq!(view.position(&content), (line, 0));
+        assert_eq!(view.window, 0..line + 2 * margin);
+
+        key(&mut view, &mut content, KeyCode::End);
+        assert_eq!(view.window, 999 - margin..1000);
+        assert_eq!(view.position(&content), (999, 0));
+
+        key(&mut view, &mut content, KeyCode::Home);
+        assert_eq!(view.window, 0..2 * margin);
+        assert_eq!(content.text.input[0].content(), "line 0");
+    }
+
+    #[test]
+    fn search_wraps_around() {
+        let source = Source::memory("a x\nb\nx c x\nd".to_string());
+        let mut view = TextView::from_source(source, TextWrap::None);
+        let mut content = Node::default();
+        view.prepare(&mut content, 0, 0);
+
+        key(&mut view, &mut content, KeyCode::Char('/'));
+        key(&mut view, &mut content, KeyCode::Char('x'));
+        key(&mut view, &mut content, KeyCode::Enter);
+        assert_eq!(view.search.current, Some((0, 2)));
+
+        let mut next = |code| {
+            key(&mut view, &mut content, code);
+            view.search.current
+        };
+        assert_eq!(next(KeyCode::Char('n')), Some((2, 0)));
+        assert_eq!(next(KeyCode::Char('n')), Some((2, 4)));
+        assert_eq!(next(KeyCode::Char('n')), Some((0, 2)));
+        assert_eq!(next(KeyCode::Char('N')), Some((2, 4)));
+        assert_eq!(next(KeyCode::Char('N')), Some((2, 0)));
+
+        key(&mut view, &mut content, KeyCode::Char('/'));
+        key(&mut view, &mut content, KeyCode::Char('y'));
+        assert_eq!(view.search.current, None);
+    }
+
+    #[test]
+    fn typed_search_stays_in_window() {
+        let mut text = vec![String::new(); 1000];
+        text[900] = "far x".to_string();
+        let mut view = TextView::from_source(Source::memory(text.join("\n")), TextWrap::None);
+        let mut content = Node::default();
+        view.prepare(&mut content, 0, 0);
+
+        key(&mut view, &mut content, KeyCode::Char('/'));
+        key(&mut view, &mut content, KeyCode::Char('x'));
+        assert!(view.window.end < 900);
+        assert_eq!(view.search.current, None);
+
+        key(&mut view, &mut content, KeyCode::Enter);
+        assert_eq!(view.search.current, Some((900, 4)));
+        assert_eq!(view.position(&content), (900, 0));
+    }
+
+    #[test]
+    fn indexed_lines_are_shown() {
+        let text = (0..1000).map(|i| format!("line {i}\n")).collect::<String>();
+        let path = std::env::temp_dir().join(format!("tui_text_view_index_{}", std::process::id()));
+        std::fs::write(&path, &text).unwrap();
+        let file = File::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Only the first 10 lines are indexed
+        let split = text.match_indices('\n').nth(9).unwrap().0 + 1;
+        let index = Arc::new(Mutex::new(LineIndex::default()));
+        index.lock().unwrap().add_chunk(&text.as_bytes()[..split]);
+        let source = Source::File {
+            file,
+            index: Arc::clone(&index),
+        };
+        let mut view = TextView::from_source(source, TextWrap::None);
+        let mut content = Node::default();
+        let mut status = Node::default();
+        view.prepare(&mut content, 0, 0);
+        view.update_status(&mut status);
+        assert_eq!(view.window, 0..10);
+        assert_eq!(status.text.input[0].content(), "Indexing… 10 lines");
+
+        // The view follows the end after `End`
+        key(&mut view, &mut content, KeyCode::End);
+        assert_eq!(view.position(&content), (9, 0));
+        index.lock().unwrap().add_chunk(&text.as_bytes()[split..]);
+        index.lock().unwrap().add_chunk(b"");
+        view.show_indexed(&mut content);
+        view.update_status(&mut status);
+        assert_eq!(view.window.end, 1001);
+        assert_eq!(view.position(&content), (1000, 0));
+        assert_eq!(status.style.size.height.computed_size(), 0);
+
+        // A window cut short by the index is extended
+        view.prepare(&mut content, 0, 0);
+        view.window = 0..10;
+        view.show_indexed(&mut content);
+        assert_eq!(view.window, 0..2 * TextView::margin());
+    }
+}
@@ -34,6 +34,12 @@ impl Debug for EventHandlers {
 }
 
 impl EventHandlers {
+    /// Returns true if no handlers are registered
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.capturing.is_empty() && self.bubbling.is_empty()
+    }
+
     #[inline]
     pub fn add_handler<F: IntoEventHandler>(&mut self, handler: F, is_capturing: bool) {
         let handler = handler.into_event_handler();
@@ -16,7 +16,7 @@ pub mod workers;
 
 pub use app::*;
 pub use canvas::Canvas;
-pub use code::Code;
+pub use code::{Code, Link};
 pub use color::{Hsl, Oklch};
 pub use elements::*;
 pub use geometry::*;
@@ -24,7 +24,8 @@ pub use handler::{EventHandlers, IntoEventHandler};
 pub use line::Line;
 pub use node::{Node, NodeHandle, NodeId, WeakNodeHandle};
 pub use style::{
-    Align, Justify, Offset, Padding, Size, SizeValue, Style,
+    Align, Grid, GridArea, GridLayout, GridPlacement, Justify, Offset, Overflow, Padding, Size,
+    SizeValue, Style, TextAlign, TextOverflow, Track,
     border::{self, Border},
 };
 pub use workers::{Message, WorkerContext};
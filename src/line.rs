@@ -5,7 +5,7 @@ use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 use crate::{
-    Code,
+    Code, TextAlign, TextOverflow,
     code::CodeUnit,
     text::{StyledUnit, VisualGrapheme},
 };
@@ -47,7 +47,7 @@ impl Line {
     /// Third value is the grapheme's `width`.
     ///
     /// Panics if the column is out of bounds.
-    fn column_to_index(&self, column: usize) -> (usize, usize, usize) {
+    pub(crate) fn column_to_index(&self, column: usize) -> (usize, usize, usize) {
         let mut columns = 0;
         for (i, c) in self.content.iter().enumerate() {
             if c.is_grapheme() {
@@ -85,6 +85,8 @@ impl Line {
                     Code::Attribute(attr) => code_unit.apply_attr(attr),
                     Code::Background(color) => code_unit.set_bg(color),
                     Code::Foreground(color) => code_unit.set_fg(color),
+                    Code::UnderlineColor(color) => code_unit.set_underline_color(color),
+                    Code::Hyperlink(link) => code_unit.set_link(link),
                 },
                 _ => {}
             }
@@ -258,7 +260,9 @@ impl Line {
                 StyledUnit::Code(code) => match code {
                     Code::Foreground(fg) => cur_code_unit.set_fg(fg),
                     Code::Background(bg) => cur_code_unit.set_bg(bg),
+                    Code::UnderlineColor(color) => cur_code_unit.set_underline_color(color),
                     Code::Attribute(attr) => cur_code_unit.apply_attr(attr),
+                    Code::Hyperlink(link) => cur_code_unit.set_link(link),
                 },
 
                 // Consume current codes and apply them if they are different from set codes
@@ -285,6 +289,13 @@ impl Line {
                         set_code_unit.set_bg(new_color);
                     }
 
+                    if set_code_unit.underline_color() != cur_code_unit.underline_color() {
+                        let new_color = cur_code_unit.underline_color().unwrap_or(Color::Reset);
+
+                        content.push(StyledUnit::Code(Code::UnderlineColor(new_color)));
+                        set_code_unit.set_underline_color(new_color);
+                    }
+
                     if set_code_unit.attrs() != cur_code_unit.attrs() {
                         content.extend(
                             set_code_unit
@@ -297,6 +308,11 @@ impl Line {
                         set_code_unit.set_attrs(cur_code_unit.attrs());
                     }
 
+                    if set_code_unit.link() != cur_code_unit.link() {
+                        content.push(StyledUnit::Code(Code::Hyperlink(cur_code_unit.link())));
+                        set_code_unit.set_link(cur_code_unit.link());
+                    }
+
                     content.push(StyledUnit::Grapheme(grapheme));
                 }
             }
@@ -322,6 +338,179 @@ impl Line {
         );
     }
 
+    /// Returns the line truncated to `width` columns, with an ellipsis at the `overflow` position.
+    /// [`TextOverflow::Clip`] cuts the line at `width`. Lines which fit are returned unchanged.
+    pub fn truncate(&self, width: usize, overflow: TextOverflow) -> Line {
+        if self.width() <= width {
+            return self.clone();
+        }
+
+        // Columns left for the graphemes
+        let columns = width.saturating_sub(1);
+        let mut line = Line::new(0);
+
+        match overflow {
+            TextOverflow::Clip => return self.cutout(0, width),
+            _ if width == 0 => {}
+            TextOverflow::EllipsisStart => {
+                line.content.push(StyledUnit::grapheme(Self::ELLIPSIS));
+                line.content.extend(self.end_part(columns));
+            }
+            TextOverflow::EllipsisMiddle => {
+                line.content.extend(self.start_part(columns - columns / 2));
+                line.content.push(StyledUnit::grapheme(Self::ELLIPSIS));
+                line.content.extend(self.end_part(columns / 2));
+            }
+            TextOverflow::EllipsisEnd => {
+                line.content.extend(self.start_part(columns));
+                line.content.push(StyledUnit::grapheme(Self::ELLIPSIS));
+            }
+        }
+
+        line
+    }
+
+    /// Returns the line with an ellipsis at its end, truncated to fit `width` columns if needed.
+    /// Trailing whitespace is removed first.
+    pub fn ellipsize_end(&self, width: usize) -> Line {
+        let mut line = self.clone();
+        line.trim_end();
+
+        if line.width() < width {
+            line.content.push(StyledUnit::grapheme(Self::ELLIPSIS));
+            line
+        } else {
+            // Force the truncation by one column
+            let mut line = line.truncate(width.saturating_sub(1), TextOverflow::Clip);
+            line.content.push(StyledUnit::grapheme(Self::ELLIPSIS));
+            line
+        }
+    }
+
+    pub(crate) const ELLIPSIS: &str = "…";
+
+    /// Returns the units of the longest start of the line which fits in `columns`, followed by
+    /// the reset codes. Wide graphemes are never split.
+    fn start_part(&self, columns: usize) -> Vec<StyledUnit> {
+        let mut width = 0;
+        let end = self
+            .content
+            .iter()
+            .position(|unit| {
+                width += unit.width();
+                width > columns
+            })
+            .unwrap_or(self.content.len());
+
+        let mut units = self.content[..end].to_vec();
+        let reset_codes = self.reset_codes_for(end).into_iter().map(StyledUnit::Code);
+        units.extend(reset_codes);
+        units
+    }
+
+    /// Returns the units of the longest end of the line which fits in `columns`, preceded by the
+    /// active codes. Wide graphemes are never split.
+    fn end_part(&self, columns: usize) -> Vec<StyledUnit> {
+        let mut width = 0;
+        let start = self
+            .content
+            .iter()
+            .rposition(|unit| {
+                width += unit.width();
+                width > columns
+            })
+            .map_or(0, |i| i + 1);
+
+        let active_codes = self.active_codes_at(start).into_codes();
+        let mut units = active_codes
+            .into_iter()
+            .map(StyledUnit::Code)
+            .collect::<Vec<_>>();
+        units.extend_from_slice(&self.content[start..]);
+        units
+    }
+
+    /// Aligns the line within `width` columns by adding spaces, after removing its trailing
+    /// whitespace. Lines which don't fit are left unchanged. Justified lines get the extra spaces
+    /// in their word gaps, unless it's the `last` line of a paragraph, which is aligned left.
+    pub fn align_to(&mut self, width: usize, align: TextAlign, last: bool) {
+        if align == TextAlign::Left {
+            return;
+        }
+
+        self.trim_end();
+        let free = width.saturating_sub(self.width());
+        if free == 0 {
+            return;
+        }
+
+        let pad_start = |line: &mut Line, count: usize| {
+            let spaces = (0..count).map(|_| StyledUnit::grapheme(" "));
+            line.content.splice(0..0, spaces);
+        };
+
+        match align {
+            TextAlign::Left => {}
+            TextAlign::Center => pad_start(self, free / 2),
+            TextAlign::Right => pad_start(self, free),
+            TextAlign::Justify if last => {}
+            TextAlign::Justify => self.justify(free),
+        }
+    }
+
+    /// Removes the trailing whitespace graphemes, codes are kept
+    pub fn trim_end(&mut self) {
+        let mut i = self.content.len();
+        while i > 0 {
+            i -= 1;
+            match &self.content[i] {
+                StyledUnit::Code(_) => continue,
+                StyledUnit::Grapheme(g) if g.str.chars().all(char::is_whitespace) => {
+                    self.content.remove(i);
+                }
+                StyledUnit::Grapheme(_) => break,
+            }
+        }
+    }
+
+    /// Spreads `free` spaces between the word gaps of the line, the first gaps get the remainder.
+    /// Leading whitespace is not a word gap.
+    fn justify(&mut self, free: usize) {
+        let is_space = |unit: &StyledUnit| matches!(unit, StyledUnit::Grapheme(g) if g.str.chars().all(char::is_whitespace));
+
+        // Indices after the last space of each word gap
+        let mut gaps = Vec::new();
+        let mut seen_word = false;
+        let mut in_gap = false;
+        for (i, unit) in self.content.iter().enumerate() {
+            if unit.is_code() {
+                continue;
+            }
+
+            if is_space(unit) {
+                in_gap = seen_word;
+            } else {
+                if in_gap {
+                    gaps.push(i);
+                }
+                seen_word = true;
+                in_gap = false;
+            }
+        }
+
+        if gaps.is_empty() {
+            return;
+        }
+
+        let share = free / gaps.len();
+        let remainder = free % gaps.len();
+        for (n, &i) in gaps.iter().enumerate().rev() {
+            let count = share + (n < remainder) as usize;
+            let spaces = (0..count).map(|_| StyledUnit::grapheme(" "));
+            self.content.splice(i..i, spaces);
+        }
+    }
+
     /// Resize the line to fit exactly `width` in grapheme column width.
     pub fn resize_to_fit(&mut self, width: usize) {
         let mut diff = width as isize - self.width() as isize;
@@ -487,3 +676,210 @@ mod cutout_tests {
         cutout(line, 10, 50, "");
     }
 }
+
+#[cfg(test)]
+mod align_tests {
+    use super::*;
+
+    fn align(line: &str, width: usize, align: TextAlign, last: bool) -> String {
+        let mut line = Line::from_string(line);
+        line.align_to(width, align, last);
+        line.to_string()
+    }
+
+    #[test]
+    fn left_unchanged() {
+        assert_eq!(align("ab  ", 6, TextAlign::Left, false), "ab  ");
+    }
+
+    #[test]
+    fn center_and_right() {
+        assert_eq!(align("abc ", 8, TextAlign::Center, false), "  abc");
+        assert_eq!(align("abc ", 8, TextAlign::Right, false), "     abc");
+        assert_eq!(align("❤️ab", 6, TextAlign::Right, false), "  ❤️ab");
+    }
+
+    #[test]
+    fn too_wide_unchanged() {
+        assert_eq!(align("abcdef", 4, TextAlign::Right, false), "abcdef");
+    }
+
+    #[test]
+    fn justify_spreads_gaps() {
+        assert_eq!(align("a b c ", 8, TextAlign::Justify, false), "a   b  c");
+        assert_eq!(align("  a  b", 8, TextAlign::Justify, false), "  a    b");
+    }
+
+    #[test]
+    fn justify_last_line_and_single_word() {
+        assert_eq!(align("a b", 6, TextAlign::Justify, true), "a b");
+        assert_eq!(align("word", 6, TextAlign::Justify, false), "word");
+    }
+
+    #[test]
+    fn keeps_codes() {
+        let mut line = Line::from_string("a b ");
+        line.content
+            .insert(0, StyledUnit::Code(Code::Foreground(Color::Red)));
+        line.content
+            .push(StyledUnit::Code(Code::Foreground(Color::Reset)));
+
+        line.align_to(5, TextAlign::Justify, false);
+        assert_eq!(line.width(), 5);
+        assert!(line.content.last().is_some_and(|u| u.is_code()));
+    }
+}
+
+#[cfg(test)]
+mod truncate_tests {
+    use super::*;
+
+    fn truncate(line: &str, width: usize, overflow: TextOverflow) -> String {
+        Line::from_string(line)
+            .truncate(width, overflow)
+            .to_string()
+    }
+
+    #[test]
+    fn fits_unchanged() {
+        assert_eq!(truncate("main.rs", 7, TextOverflow::EllipsisEnd), "main.rs");
+    }
+
+    #[test]
+    fn ellipsis_positions() {
+        let path = "/home/user/src/main.rs";
+        assert_eq!(truncate(path, 10, TextOverflow::EllipsisEnd), "/home/use…");
+        assert_eq!(
+            truncate(path, 10, TextOverflow::EllipsisStart),
+            "…c/main.rs"
+        );
+        assert_eq!(
+            truncate(path, 10, TextOverflow::EllipsisMiddle),
+            "/home…n.rs"
+        );
+        assert_eq!(truncate(path, 10, TextOverflow::Clip), "/home/user");
+    }
+
+    #[test]
+    fn wide_graphemes_not_split() {
+        assert_eq!(truncate("漢字漢字", 6, TextOverflow::EllipsisEnd), "漢字…");
+        assert_eq!(
+            truncate("漢字漢字", 6, TextOverflow::EllipsisStart),
+            "…漢字"
+        );
+        assert_eq!(truncate("a漢字", 3, TextOverflow::EllipsisEnd), "a…");
+    }
+
+    #[test]
+    fn keeps_codes_balanced() {
+        let red = StyledUnit::Code(Code::Foreground(Color::Red));
+        let reset = StyledUnit::Code(Code::Foreground(Color::Reset));
+        let mut line = Line::from_string("abcdef");
+        line.content.insert(0, red.clone());
+        line.content.push(reset.clone());
+
+        let start = line.truncate(4, TextOverflow::EllipsisStart);
+        assert_eq!(start.content[1], red);
+        assert_eq!(start.content.last(), Some(&reset));
+        assert_eq!(start.width(), 4);
+
+        let end = line.truncate(4, TextOverflow::EllipsisEnd);
+        assert_eq!(end.content[0], red);
+        assert_eq!(end.content[4], reset);
+        assert_eq!(end.width(), 4);
+    }
+
+    #[test]
+    fn ellipsize_end() {
+        let line = Line::from_string("first line ");
+        assert_eq!(line.ellipsize_end(20).to_string(), "first line…");
+        assert_eq!(line.ellipsize_end(10).to_string(), "first lin…");
+    }
+}
+
+#[cfg(test)]
+mod hyperlink_tests {
+    use crate::code::Link;
+
+    use super::*;
+
+    fn linked(content: &str, url: &str) -> Line {
+        let mut line = Line::from_string(content);
+        line.content
+            .insert(0, StyledUnit::Code(Code::Hyperlink(Some(Link::new(url)))));
+        line.content.push(StyledUnit::Code(Code::Hyperlink(None)));
+        line
+    }
+
+    /// Returns the line content with links as `<` and `>`
+    fn render(line: &Line) -> String {
+        line.content
+            .iter()
+            .map(|unit| match unit {
+                StyledUnit::Code(Code::Hyperlink(Some(_))) => "<".to_string(),
+                StyledUnit::Code(Code::Hyperlink(None)) => ">".to_string(),
+                StyledUnit::Code(_) => String::new(),
+                StyledUnit::Grapheme(g) => g.str.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn interned_and_displayed() {
+        let link = Link::new("https://example.com/\x1b");
+        assert_eq!(link, Link::new("https://example.com/"));
+        assert_eq!(&*link.url(), "https://example.com/");
+        assert!(
+            Code::Hyperlink(Some(link))
+                .to_string()
+                .ends_with(";https://example.com/\x1b\\")
+        );
+        assert_eq!(Code::Hyperlink(None).to_string(), "\x1b]8;;\x1b\\");
+        assert_eq!(
+            Code::Hyperlink(Some(link)).into_reset(),
+            Code::Hyperlink(None)
+        );
+    }
+
+    #[test]
+    fn cutout_balanced() {
+        let line = linked("abcdef", "https://a.b");
+        assert_eq!(render(&line.cutout(2, 2)), "<cd>");
+        assert_eq!(render(&line.cutout(4, 9)), "<ef>");
+    }
+
+    #[test]
+    fn paste_on_top_balanced() {
+        let mut line = linked("abcdef", "https://a.b");
+        line.paste_on_top(&Line::from_string("XY"), 2);
+        assert_eq!(render(&line), "<ab>XY<ef>");
+
+        let mut line = Line::from_string("abcdef");
+        line.paste_on_top(&linked("XY", "https://a.b"), 2);
+        line.paste_on_top(&Line::from_string("Z"), 3);
+        assert_eq!(render(&line), "ab<X>Z<>ef");
+
+        line.prune_redundant_codes();
+        assert_eq!(render(&line), "ab<X>Zef");
+    }
+
+    #[test]
+    fn prune_keeps_pairs() {
+        let mut line = linked("abcdef", "https://a.b");
+        line.paste_on_top(&linked("cd", "https://a.b"), 2);
+        line.paste_on_top(&linked("ef", "https://c.d"), 4);
+        line.prune_redundant_codes();
+        assert_eq!(render(&line), "<abcd<ef>");
+
+        let links = line.content.iter().filter_map(|unit| match unit {
+            StyledUnit::Code(Code::Hyperlink(link)) => Some(*link),
+            _ => None,
+        });
+        let expected = [
+            Some(Link::new("https://a.b")),
+            Some(Link::new("https://c.d")),
+            None,
+        ];
+        assert!(links.eq(expected));
+    }
+}
@@ -1,4 +1,6 @@
 mod handle;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod utils;
 
 pub use handle::{NodeHandle, WeakNodeHandle};
@@ -9,7 +11,9 @@ use std::{
 };
 
 use crate::{
-    Canvas, Context, EventHandlers, HitMap, IntoEventHandler, Offset, Size, Style, Viewport,
+    Canvas, Context, EventHandlers, Grid, GridArea, GridLayout, HitMap, IntoEventHandler, Line,
+    Offset, Overflow, Size, Style, Track, Viewport,
+    style::resolve_tracks,
     text::Text,
     workers::{WorkerFn, Workers},
 };
@@ -60,6 +64,9 @@ pub struct Node {
     pub class: String,
     pub style: Style,
     pub text: Text,
+    /// Scroll offset of the content `(x, y)`, clamped to the overflowing content size when the
+    /// canvas is computed. The node has to be recomputed after changing it
+    pub scroll: (u16, u16),
 
     /// Weak ref to parent. Use with caution to prevent deadlocks or memory leaks
     pub parent: Option<WeakNodeHandle>,
@@ -101,6 +108,22 @@ pub struct NodeCache {
     pub viewport: Viewport,
     /// Latest canvas position, computed at [`Node::calculate_canvas`]
     pub canvas_position: (i16, i16),
+    /// Latest content size (text and relative children), including the overflowing part,
+    /// computed at [`Node::calculate_canvas`]
+    pub content_size: (u16, u16),
+    /// Latest grid layout of a grid container, computed at [`Node::calculate_percentage_size`]
+    pub grid: Option<GridLayout>,
+}
+
+#[derive(Debug, Default, Clone)]
+/// A line of relative children in a flex container, there are multiple lines only when wrapping.
+struct FlexLine {
+    /// Indices of the children in this line
+    children: Vec<usize>,
+    /// Total main axis size of the children, including gaps
+    main_size: u16,
+    /// Largest cross axis size of the children
+    cross_size: u16,
 }
 
 impl Node {
@@ -114,6 +137,7 @@ impl Node {
             class: String::default(),
             style: Style::default(),
             text: Text::default(),
+            scroll: (0, 0),
             parent: Option::default(),
             children: Vec::default(),
             handlers: Rc::default(),
@@ -159,6 +183,17 @@ impl Node {
         self.canvas.position
     }
 
+    /// Returns the absolute position of the node's content box, which is inside the padding and
+    /// border
+    #[inline]
+    pub fn content_position(&self) -> (i16, i16) {
+        let (x, y) = self.canvas.position;
+        let left = self.style.padding.left + self.style.border.left();
+        let top = self.style.padding.top + self.style.border.top();
+
+        (x + left as i16, y + top as i16)
+    }
+
     /// Returns the focus cursor position, which will either be the text's cursor position, or the
     /// content-start of the node's canvas.
     #[inline]
@@ -172,13 +207,49 @@ impl Node {
         let y = py as i32 + xh as i32;
 
         if let Some((cx, cy)) = self.text.cursor {
-            return ((x + cx as i32).max(0) as u16, (y + cy as i32).max(0) as u16);
+            let x = x + cx as i32 - self.scroll.0 as i32;
+            let y = y + cy as i32 - self.scroll.1 as i32;
+            return (x.max(0) as u16, y.max(0) as u16);
         }
 
         // If the cursor is not set, return the base position
         (x.max(0) as u16, y.max(0) as u16)
     }
 
+    /// Returns the maximum scroll offset `(x, y)`, which is the size of the content overflowing
+    /// the node's content box
+    pub fn max_scroll(&self) -> (u16, u16) {
+        let (width, height) = self.cache().content_size;
+        (
+            width.saturating_sub(self.style.clamped_width()),
+            height.saturating_sub(self.style.clamped_height()),
+        )
+    }
+
+    /// Scrolls the content by `(dx, dy)` along the [scrollable](Overflow::is_scrollable) axes,
+    /// clamped to [`Node::max_scroll`]. Returns true if the scroll offset has changed, in which
+    /// case the node has to be recomputed.
+    pub fn scroll_by(&mut self, dx: i32, dy: i32) -> bool {
+        let (overflow_x, overflow_y) = self.style.overflow;
+        let (max_x, max_y) = self.max_scroll();
+
+        let scroll = |scroll: u16, delta: i32, max: u16, overflow: Overflow| {
+            if !overflow.is_scrollable() {
+                return scroll;
+            }
+            (scroll as i32 + delta).clamp(0, max.max(scroll) as i32) as u16
+        };
+
+        let new_scroll = (
+            scroll(self.scroll.0, dx, max_x, overflow_x),
+            scroll(self.scroll.1, dy, max_y, overflow_y),
+        );
+
+        let changed = new_scroll != self.scroll;
+        self.scroll = new_scroll;
+        changed
+    }
+
     /// Primitive calculation of `pos - node.canvas.position`, clamped to 0
     #[inline]
     pub fn relative_position(&self, x: i16, y: i16) -> (u16, u16) {
@@ -285,18 +356,45 @@ impl Node {
     ///
     /// # Note
     /// Clamps the size
+    #[inline]
     pub fn calculate_percentage_size(&mut self, parent_available_size: Size) {
+        self.calculate_flexed_size(parent_available_size, (None, None));
+    }
+
+    /// Same as [`Node::calculate_percentage_size`], but the computed `(width, height)` is forced
+    /// to the `forced` values when set. Used by the parent's flex layout to apply the resolved
+    /// flex size, forced values should already be clamped.
+    fn calculate_flexed_size(
+        &mut self,
+        parent_available_size: Size,
+        forced: (Option<u16>, Option<u16>),
+    ) {
         self.cache_mut().parent_available_size = parent_available_size;
 
         // Calculate the size of this node
         self.style
             .compute_percentage_size(parent_available_size, &mut self.text);
 
+        // Apply the forced flex size, text has to be rewrapped to the new width
+        if let Some(width) = forced.0 {
+            self.style.size.width = self.style.size.width.set_computed_size(width);
+            self.text.wrap_text(width);
+        }
+        if let Some(height) = forced.1 {
+            self.style.size.height = self.style.size.height.set_computed_size(height);
+        }
+
         // Calculate the available content size of tis node
         let available_content_size = self.available_content_size();
 
         // Text size is used only for auto-size calculation
-        let (text_width, text_height) = self.text.get_visual_size();
+        let (text_width, text_height) = self.style.text_size(&self.text);
+
+        // Grid containers place their children on tracks instead
+        if let Some(grid) = self.style.grid.clone() {
+            self.calculate_grid_size(&grid, forced, (text_width, text_height));
+            return;
+        }
 
         // Either max_size or total_size depending on flex direction, for auto-size calculation
         let mut width = text_width;
@@ -312,6 +410,13 @@ impl Node {
                 continue;
             }
 
+            // Resize the child to its flex basis
+            if !child.style.flex_basis.is_auto() {
+                let basis = child.flex_basis_size(available_content_size, self.style.flex_row);
+                let forced = Self::main_axis_size(basis, self.style.flex_row);
+                child.calculate_flexed_size(available_content_size, forced);
+            }
+
             // Get total clamped child size, if not a percentage
             let child_width =
                 child.style.total_width() * !self.style.size.width.is_percent() as u16;
@@ -332,13 +437,122 @@ impl Node {
 
         // Add text height as flex-col, since text isn't part of the flexbox
         let has_text = text_height > 0;
-        height += text_height + self.style.gap.1 * had_first_child as u16 * has_text as u16;
+        let text_height = text_height + self.style.gap.1 * had_first_child as u16 * has_text as u16;
+        height += text_height;
 
         // Apply the resized accumulated size if auto
-        if self.style.size.width.is_auto() {
+        if self.style.size.width.is_auto() && forced.0.is_none() {
             self.style.size.width = self.style.size.width.set_computed_size(width);
         }
-        if self.style.size.height.is_auto() {
+        if self.style.size.height.is_auto() && forced.1.is_none() {
+            self.style.size.height = self.style.size.height.set_computed_size(height);
+        }
+
+        // Clamp the size
+        self.style.size = self
+            .style
+            .size
+            .clamp_computed_size(self.style.min_size, self.style.max_size);
+
+        // Grow and shrink children to fill the final content size, line by line
+        let lines = self.flex_lines(text_height);
+        let mut resized = false;
+        for line in &lines {
+            resized |=
+                self.resolve_flexible_children(&line.children, available_content_size, text_height);
+        }
+
+        // Flexed sizes may have changed the wrapped text height of the children, and wrapped
+        // lines stack along the cross axis
+        if resized || self.style.flex_wrap {
+            let lines = if resized {
+                self.flex_lines(text_height)
+            } else {
+                lines
+            };
+            self.resize_auto_cross_size(&lines, text_width, text_height, forced);
+        }
+    }
+
+    /// Resolves the grid tracks and sizes the children to their grid areas, auto-sized children
+    /// are stretched to fill them. If the grid is auto-sized, it fits its tracks, in which case
+    /// percentage and fraction tracks behave like `auto` tracks.
+    fn calculate_grid_size(
+        &mut self,
+        grid: &Grid,
+        forced: (Option<u16>, Option<u16>),
+        (text_width, text_height): (u16, u16),
+    ) {
+        let content_size = self.style.size;
+        for child in &self.children {
+            child.borrow_mut().calculate_percentage_size(content_size);
+        }
+
+        let areas = self.grid_areas(grid);
+
+        // Text is placed above the grid
+        let has_items = !areas.is_empty();
+        let text_height = text_height + self.style.gap.1 * (text_height > 0 && has_items) as u16;
+
+        let auto_width = self.style.size.width.is_auto() && forced.0.is_none();
+        let auto_height = self.style.size.height.is_auto() && forced.1.is_none();
+
+        // Resolve the columns first, fitting the children to them may change their height
+        let content_width = (!auto_width).then(|| self.style.clamped_width());
+        let columns = self.resolve_grid_tracks(
+            &grid.column_tracks(),
+            &areas,
+            content_width,
+            true,
+            Style::total_width,
+        );
+
+        let mut layout = GridLayout {
+            columns,
+            rows: Vec::new(),
+            areas,
+            gap: self.style.gap,
+        };
+
+        for &(i, area) in &layout.areas {
+            let (width, _) = layout.area_size(area);
+            let mut child = self.children[i].borrow_mut();
+
+            let available_size = Size::from_cells(width, content_size.height.computed_size());
+            let forced = (child.grid_stretch_size(width, true), None);
+            child.calculate_flexed_size(available_size, forced);
+        }
+
+        // Resolve the rows, including implicit ones
+        let content_height =
+            (!auto_height).then(|| self.style.clamped_height().saturating_sub(text_height));
+        layout.rows = self.resolve_grid_tracks(
+            &grid.row_tracks(Self::grid_row_count(&layout.areas)),
+            &layout.areas,
+            content_height,
+            false,
+            Style::total_height,
+        );
+
+        for &(i, area) in &layout.areas {
+            let (width, height) = layout.area_size(area);
+            let mut child = self.children[i].borrow_mut();
+
+            let forced = (
+                child.grid_stretch_size(width, true),
+                child.grid_stretch_size(height, false),
+            );
+            child.calculate_flexed_size(Size::from_cells(width, height), forced);
+        }
+
+        // Fit the auto size to the tracks
+        let (grid_width, grid_height) = layout.size();
+        if auto_width {
+            let width = grid_width.max(text_width);
+            self.style.size.width = self.style.size.width.set_computed_size(width);
+        }
+        if auto_height {
+            let height = grid_height.saturating_add(text_height);
             self.style.size.height = self.style.size.height.set_computed_size(height);
         }
 
@@ -347,6 +561,380 @@ impl Node {
             .style
             .size
             .clamp_computed_size(self.style.min_size, self.style.max_size);
+
+        self.cache_mut().grid = Some(layout);
+    }
+
+    /// Places the relative children on the `grid`, returns their indices with their grid areas
+    fn grid_areas(&self, grid: &Grid) -> Vec<(usize, GridArea)> {
+        let (children, placements): (Vec<_>, Vec<_>) = self
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, child)| {
+                let child = child.borrow();
+                let is_relative = !child.style.offset.is_absolute();
+                is_relative.then_some((i, child.style.grid_placement))
+            })
+            .unzip();
+
+        children
+            .into_iter()
+            .zip(grid.place_items(&placements))
+            .collect()
+    }
+
+    /// Returns the number of rows taken by the grid `areas`
+    fn grid_row_count(areas: &[(usize, GridArea)]) -> usize {
+        areas
+            .iter()
+            .map(|(_, area)| (area.row + area.row_span) as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Resolves the columns (or rows) of a grid, fitting the `size` of the children in `areas`
+    fn resolve_grid_tracks(
+        &self,
+        tracks: &[Track],
+        areas: &[(usize, GridArea)],
+        content_size: Option<u16>,
+        columns: bool,
+        size: fn(&Style) -> u16,
+    ) -> Vec<u16> {
+        let gap = if columns {
+            self.style.gap.0
+        } else {
+            self.style.gap.1
+        };
+
+        let items = areas
+            .iter()
+            .map(|&(i, area)| {
+                let child = self.children[i].borrow();
+                if columns {
+                    (area.column, area.column_span, size(&child.style))
+                } else {
+                    (area.row, area.row_span, size(&child.style))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        resolve_tracks(tracks, content_size, gap, &items)
+    }
+
+    /// Returns the clamped size which stretches this grid item to its area `size`, if the item
+    /// is auto-sized along the axis
+    fn grid_stretch_size(&self, size: u16, width: bool) -> Option<u16> {
+        let style = &self.style;
+        let (value, extra, min, max) = if width {
+            let (min, max) = (style.min_size.width, style.max_size.width);
+            (style.size.width, style.extra_width(), min, max)
+        } else {
+            let (min, max) = (style.min_size.height, style.max_size.height);
+            (style.size.height, style.extra_height(), min, max)
+        };
+
+        value.is_auto().then(|| {
+            size.saturating_sub(extra)
+                .min(max.computed_size())
+                .max(min.computed_size())
+        })
+    }
+
+    /// Resizes the auto cross axis size to fit the flex `lines`, stacked with the cross gap.
+    fn resize_auto_cross_size(
+        &mut self,
+        lines: &[FlexLine],
+        text_width: u16,
+        text_height: u16,
+        forced: (Option<u16>, Option<u16>),
+    ) {
+        let flex_row = self.style.flex_row;
+        let cross_gap = if flex_row {
+            self.style.gap.1
+        } else {
+            self.style.gap.0
+        };
+
+        let gaps = cross_gap * lines.len().saturating_sub(1) as u16;
+        let cross_size = lines
+            .iter()
+            .map(|line| line.cross_size)
+            .fold(gaps, u16::saturating_add);
+
+        let style = &mut self.style;
+        if flex_row && style.size.height.is_auto() && forced.1.is_none() {
+            style.size.height = style
+                .size
+                .height
+                .set_computed_size(cross_size.saturating_add(text_height))
+                .clamp_computed_size(style.min_size.height, style.max_size.height);
+        } else if !flex_row && style.size.width.is_auto() && forced.0.is_none() {
+            style.size.width = style
+                .size
+                .width
+                .set_computed_size(cross_size.max(text_width))
+                .clamp_computed_size(style.min_size.width, style.max_size.width);
+        }
+    }
+
+    /// Splits the relative children into flex lines. Without `flex_wrap` all of them are in a
+    /// single line, otherwise a new line is started when a child would overflow the main axis
+    /// content size.
+    ///
+    /// `text_height` is the height taken by the text (and its gap), which is only subtracted in
+    /// the column direction.
+    fn flex_lines(&self, text_height: u16) -> Vec<FlexLine> {
+        let flex_row = self.style.flex_row;
+        let (max_main_size, gap) = if flex_row {
+            (self.style.clamped_width(), self.style.gap.0)
+        } else {
+            (
+                self.style.clamped_height().saturating_sub(text_height),
+                self.style.gap.1,
+            )
+        };
+
+        let mut lines = Vec::new();
+        let mut line = FlexLine::default();
+
+        for (i, child) in self.children.iter().enumerate() {
+            let child = child.borrow();
+            if child.style.offset.is_absolute() {
+                continue;
+            }
+
+            let (width, height) = child.style.total_size();
+            let (main_size, cross_size) = if flex_row {
+                (width, height)
+            } else {
+                (height, width)
+            };
+
+            let had_first_child = !line.children.is_empty();
+            let line_main_size = line
+                .main_size
+                .saturating_add(gap * had_first_child as u16)
+                .saturating_add(main_size);
+
+            if self.style.flex_wrap && had_first_child && line_main_size > max_main_size {
+                lines.push(std::mem::take(&mut line));
+                line.main_size = main_size;
+            } else {
+                line.main_size = line_main_size;
+            }
+
+            line.children.push(i);
+            line.cross_size = line.cross_size.max(cross_size);
+        }
+
+        if !line.children.is_empty() {
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    /// Returns the `(width, height)` forced size tuple with `size` set on the main axis
+    #[inline]
+    fn main_axis_size(size: u16, flex_row: bool) -> (Option<u16>, Option<u16>) {
+        if flex_row {
+            (Some(size), None)
+        } else {
+            (None, Some(size))
+        }
+    }
+
+    /// Returns the clamped content size from `flex_basis` along the parent's main axis.
+    #[inline]
+    fn flex_basis_size(&self, parent_available_size: Size, flex_row: bool) -> u16 {
+        let style = &self.style;
+        let (parent, min, max, extra) = if flex_row {
+            let (min, max) = (style.min_size.width, style.max_size.width);
+            (parent_available_size.width, min, max, style.extra_width())
+        } else {
+            let (min, max) = (style.min_size.height, style.max_size.height);
+            (parent_available_size.height, min, max, style.extra_height())
+        };
+
+        // Percentages include padding and borders, same as percentage sizes
+        let basis = style.flex_basis.compute_size(parent, 0);
+        let basis = if basis.is_percent() {
+            basis.set_computed_size(basis.computed_size().saturating_sub(extra))
+        } else {
+            basis
+        };
+
+        basis.clamp_computed_size(min, max).computed_size()
+    }
+
+    /// Distributes the free main axis content space between the relative `children` of a single
+    /// flex line, given by their indices. Positive space grows children by their `flex_grow`
+    /// factor, negative space shrinks them by their `flex_shrink` factor weighted by their
+    /// current size.
+    ///
+    /// `text_height` is the height taken by the text (and its gap), which is only subtracted in
+    /// the column direction. Returns `true` if any child was resized. Space lost to a child's
+    /// `min_size` or `max_size` clamping is distributed again between its siblings.
+    fn resolve_flexible_children(
+        &mut self,
+        children: &[usize],
+        available_content_size: Size,
+        text_height: u16,
+    ) -> bool {
+        let flex_row = self.style.flex_row;
+        let (content_size, gap) = if flex_row {
+            (self.style.clamped_width(), self.style.gap.0)
+        } else {
+            (
+                self.style.clamped_height().saturating_sub(text_height),
+                self.style.gap.1,
+            )
+        };
+
+        // (index, base content size, total size, grow, shrink, min, max)
+        let mut items = Vec::new();
+        for &i in children {
+            let child = self.children[i].borrow();
+            let style = &child.style;
+            let item = if flex_row {
+                (
+                    i,
+                    style.clamped_width(),
+                    style.total_width(),
+                    style.flex_grow,
+                    style.flex_shrink,
+                    style.min_size.width.computed_size(),
+                    style.max_size.width.computed_size(),
+                )
+            } else {
+                (
+                    i,
+                    style.clamped_height(),
+                    style.total_height(),
+                    style.flex_grow,
+                    style.flex_shrink,
+                    style.min_size.height.computed_size(),
+                    style.max_size.height.computed_size(),
+                )
+            };
+            items.push(item);
+        }
+
+        let gaps = gap as i32 * items.len().saturating_sub(1) as i32;
+        let used = items.iter().map(|item| item.2 as i32).sum::<i32>() + gaps;
+        let growing = content_size as i32 > used;
+        if content_size as i32 == used {
+            return false;
+        }
+
+        // Weight of each item, grow factor for free space, shrink factor * base size for overflow
+        let weights = items
+            .iter()
+            .map(|&(_, base, _, grow, shrink, ..)| {
+                if growing {
+                    grow as u64
+                } else {
+                    shrink as u64 * base as u64
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Items are frozen at their target size once it violates their min or max size, the
+        // space is distributed again between the rest
+        let mut targets = items.iter().map(|item| item.1).collect::<Vec<_>>();
+        let mut frozen = weights
+            .iter()
+            .map(|&weight| weight == 0)
+            .collect::<Vec<_>>();
+        while frozen.contains(&false) {
+            for (j, item) in items.iter().enumerate() {
+                if !frozen[j] {
+                    targets[j] = item.1;
+                }
+            }
+
+            // Sizes with the extra size of the items
+            let used = items
+                .iter()
+                .zip(&targets)
+                .map(|(item, &target)| (item.2 - item.1 + target) as i32)
+                .sum::<i32>()
+                + gaps;
+            let free = content_size as i32 - used;
+            if free == 0 || (free > 0) != growing {
+                break;
+            }
+
+            // Proportional shares, the remainder is distributed one cell at a time
+            let total_weight = (0..items.len())
+                .filter(|&j| !frozen[j])
+                .map(|j| weights[j])
+                .sum::<u64>();
+            let space = free.unsigned_abs() as u64;
+            let mut shares = (0..items.len())
+                .map(|j| match frozen[j] {
+                    true => 0,
+                    false => space * weights[j] / total_weight,
+                })
+                .collect::<Vec<_>>();
+            let mut remainder = space - shares.iter().sum::<u64>();
+            for j in 0..items.len() {
+                if remainder == 0 {
+                    break;
+                }
+                if !frozen[j] && weights[j] > 0 {
+                    shares[j] += 1;
+                    remainder -= 1;
+                }
+            }
+
+            // Clamp the targets, the sign of the total violation decides which items freeze
+            let mut violations = vec![0; items.len()];
+            for (j, &(_, base, _, _, _, min, max)) in items.iter().enumerate() {
+                if frozen[j] {
+                    continue;
+                }
+
+                let share = shares[j].min(u16::MAX as u64) as i64;
+                let unclamped = if growing {
+                    base as i64 + share
+                } else {
+                    base as i64 - share
+                };
+                let clamped = (unclamped.clamp(0, u16::MAX as i64) as u16)
+                    .min(max)
+                    .max(min);
+                targets[j] = clamped;
+                violations[j] = clamped as i64 - unclamped;
+            }
+
+            let total_violation = violations.iter().sum::<i64>();
+            if total_violation == 0 {
+                break;
+            }
+            for (j, violation) in violations.into_iter().enumerate() {
+                if violation != 0 && (violation > 0) == (total_violation > 0) {
+                    frozen[j] = true;
+                }
+            }
+        }
+
+        let mut resized = false;
+        for (&(i, base, ..), &size) in items.iter().zip(&targets) {
+            if size == base {
+                continue;
+            }
+
+            let forced = Self::main_axis_size(size, flex_row);
+            self.children[i]
+                .borrow_mut()
+                .calculate_flexed_size(available_content_size, forced);
+            resized = true;
+        }
+
+        resized
     }
 
     /// Calculates the auto size and intrinsic size of the node. It's the first step of size
@@ -362,6 +950,48 @@ impl Node {
         let mut width = self.style.size.width.computed_size();
         let mut height = self.style.size.height.computed_size();
 
+        // Grid containers fit their auto-sized tracks
+        if let Some(grid) = self.style.grid.clone() {
+            for child in &self.children {
+                child.borrow_mut().calculate_auto_intrinsic_size();
+            }
+
+            let areas = self.grid_areas(&grid);
+            let columns = self.resolve_grid_tracks(
+                &grid.column_tracks(),
+                &areas,
+                None,
+                true,
+                Style::total_width_unclamped,
+            );
+            let rows = self.resolve_grid_tracks(
+                &grid.row_tracks(Self::grid_row_count(&areas)),
+                &areas,
+                None,
+                false,
+                Style::total_height_unclamped,
+            );
+
+            let gap = self.style.gap.1 * (height > 0 && !areas.is_empty()) as u16;
+            let layout = GridLayout {
+                columns,
+                rows,
+                areas,
+                gap: self.style.gap,
+            };
+            let (grid_width, grid_height) = layout.size();
+
+            if self.style.size.width.is_auto() {
+                let width = width.max(grid_width);
+                self.style.size.width = self.style.size.width.set_computed_size(width);
+            }
+            if self.style.size.height.is_auto() {
+                let height = height + gap + grid_height;
+                self.style.size.height = self.style.size.height.set_computed_size(height);
+            }
+            return;
+        }
+
         let mut had_first_child = false;
         for child in self.children.iter() {
             let mut child = child.borrow_mut();
@@ -411,22 +1041,103 @@ impl Node {
             self.style.padding.top as i16 + self.style.border.top() as i16,
         ));
 
+        self.cache_mut().canvas_position = offset_position.tuple();
+
+        let mut canvas = self.calculate_content_canvas(offset_position, content_position);
+
+        // The content may have shrunk since it was scrolled, clamp the offset and recompute
+        let (max_x, max_y) = self.max_scroll();
+        let scroll = (self.scroll.0.min(max_x), self.scroll.1.min(max_y));
+        if scroll != self.scroll {
+            self.scroll = scroll;
+            canvas = self.calculate_content_canvas(offset_position, content_position);
+        }
+
+        // Normalize the canvas to a block based on the style
+        canvas.normalize(&self.style);
+
+        // Add block styling
+        canvas.add_padding(&self.style);
+        canvas.add_fg(self.style.fg);
+        canvas.add_bg(self.style.bg);
+        canvas.add_border(&self.style);
+
+        self.canvas = canvas;
+    }
+
+    /// Computes the canvas with the text and the children, shifted by the scroll offset, and
+    /// caches the content size.
+    fn calculate_content_canvas(
+        &mut self,
+        offset_position: Offset,
+        content_position: Offset,
+    ) -> Canvas {
         let mut canvas = Canvas {
             position: offset_position.tuple(),
             buffer: vec![],
         };
-        self.cache_mut().canvas_position = canvas.position;
 
         // Add text (before children)
-        canvas.add_text(&self.text, self.style.size);
+        let text_height = canvas.add_text(&self.text, &self.style, self.scroll);
 
         // Start children after text (always flex-col)
         let y_after_text = {
-            let height = canvas.height() as i16;
+            let height = text_height as i16;
             let gap_row = self.style.gap.1 as i16 * (height > 0) as i16;
             height + gap_row
         };
 
+        // Scrolled content starts before the content box
+        let scrolled_position =
+            content_position.add_tuple((-(self.scroll.0 as i16), -(self.scroll.1 as i16)));
+
+        if self.style.grid.is_some() {
+            // Grid children don't extend the canvas either
+            self.calculate_grid_children_canvas(scrolled_position, y_after_text);
+        } else if self.style.flex_wrap {
+            // Wrapped children don't extend the canvas, it's normalized to the size below
+            self.calculate_wrapped_children_canvas(scrolled_position, y_after_text);
+        } else {
+            self.calculate_children_canvas(&mut canvas, scrolled_position, y_after_text);
+        }
+
+        // Content size is measured from the scrolled content start
+        let text_width = self.text.visual.iter().map(|l| l.width()).max();
+        let mut content_size = (
+            text_width.unwrap_or(0).min(u16::MAX as usize) as u16,
+            text_height.min(u16::MAX as usize) as u16,
+        );
+
+        let (start_x, start_y) = scrolled_position.tuple();
+        for child in &self.children {
+            let child = child.borrow();
+            if child.style.offset.is_absolute() {
+                continue;
+            }
+
+            let (x, y) = child.canvas.position;
+            let (width, height) = child.style.total_size();
+            let end_x = (x as i32 - start_x as i32 + width as i32).clamp(0, u16::MAX as i32);
+            let end_y = (y as i32 - start_y as i32 + height as i32).clamp(0, u16::MAX as i32);
+
+            content_size.0 = content_size.0.max(end_x as u16);
+            content_size.1 = content_size.1.max(end_y as u16);
+        }
+
+        self.cache_mut().content_size = content_size;
+        canvas
+    }
+
+    /// Computes the canvas of each child in a single flex line, positioned with justify and
+    /// align, and extends `canvas` with their blank copies.
+    ///
+    /// - `y_after_text` is the row at which the children start, after the text and its gap.
+    fn calculate_children_canvas(
+        &mut self,
+        canvas: &mut Canvas,
+        content_position: Offset,
+        y_after_text: i16,
+    ) {
         let mut extra_offset = (0, y_after_text);
         let mut include_gap = false;
 
@@ -486,21 +1197,14 @@ impl Node {
             }
 
             // Increment the canvas offset with justify spaced logic
-            if self.style.justify.is_spaced() {
-                let adjusted_children_count = if self.style.justify.is_space_evenly() {
-                    relative_children_count + 1 // adds an extra start offset
-                } else if self.style.justify.is_space_between() {
-                    relative_children_count - 1 // between two children
-                } else {
-                    relative_children_count // no adjustment
-                };
-
-                let offset = (free_content_size / adjusted_children_count as i16).max(0);
-                if self.style.flex_row {
-                    extra_offset.0 += offset;
-                } else {
-                    extra_offset.1 += offset;
-                }
+            let offset = self
+                .style
+                .justify
+                .get_item_spacing(free_content_size, relative_children_count);
+            if self.style.flex_row {
+                extra_offset.0 += offset;
+            } else {
+                extra_offset.1 += offset;
             }
 
             // Add the child canvas to this node's canvas
@@ -509,17 +1213,98 @@ impl Node {
             // Include gap after first child is seen
             include_gap = true;
         }
+    }
 
-        // Normalize the canvas to a block based on the style
-        canvas.normalize(&self.style);
+    /// Computes the canvas of each child in wrapped flex lines. Each line is justified on its own,
+    /// and its children are aligned within the line's cross size. Lines are stacked along the cross
+    /// axis, separated by the cross gap.
+    ///
+    /// - `y_after_text` is the row at which the children start, after the text and its gap.
+    fn calculate_wrapped_children_canvas(&mut self, content_position: Offset, y_after_text: i16) {
+        let flex_row = self.style.flex_row;
+        let start_position = content_position.add_tuple((0, y_after_text));
 
-        // Add block styling
-        canvas.add_padding(&self.style);
-        canvas.add_fg(self.style.fg);
-        canvas.add_bg(self.style.bg);
-        canvas.add_border(&self.style);
+        let (gap_column, gap_row) = self.style.gap;
+        let (content_main_size, main_gap, cross_gap) = if flex_row {
+            (self.style.clamped_width() as i16, gap_column, gap_row)
+        } else {
+            let height = self.style.clamped_height() as i16 - y_after_text;
+            (height, gap_row, gap_column)
+        };
 
-        self.canvas = canvas;
+        // Absolute children are not part of any line
+        for child in &self.children {
+            let mut child = child.borrow_mut();
+            if child.style.offset.is_absolute() {
+                // Use parent's 0,0 for absolutely relative children
+                child.calculate_canvas(content_position);
+            }
+        }
+
+        let mut cross_offset = 0i16;
+        for line in self.flex_lines(y_after_text.max(0) as u16) {
+            let count = line.children.len();
+            let free_line_size = content_main_size - line.main_size as i16;
+            let justify = self.style.justify;
+
+            let (start_x, start_y) = justify.get_start_offset(free_line_size, count, flex_row);
+            let mut main_offset = if flex_row { start_x } else { start_y };
+            let spacing = justify.get_item_spacing(free_line_size, count);
+
+            for i in line.children {
+                let mut child = self.children[i].borrow_mut();
+
+                let (width, height) = child.style.total_size();
+                let (main_size, cross_size) = if flex_row {
+                    (width, height)
+                } else {
+                    (height, width)
+                };
+
+                let align_offset = self
+                    .style
+                    .align
+                    .alignment_offset(line.cross_size, cross_size);
+                let cross = cross_offset + align_offset as i16;
+                let offset = if flex_row {
+                    (main_offset, cross)
+                } else {
+                    (cross, main_offset)
+                };
+
+                child.calculate_canvas(start_position.add_tuple(offset));
+                main_offset += main_size as i16 + main_gap as i16 + spacing;
+            }
+
+            cross_offset += line.cross_size as i16 + cross_gap as i16;
+        }
+    }
+
+    /// Computes the canvas of each child at the start of its grid area, resolved in the latest
+    /// [grid layout](NodeCache::grid).
+    ///
+    /// - `y_after_text` is the row at which the grid starts, after the text and its gap.
+    fn calculate_grid_children_canvas(&mut self, content_position: Offset, y_after_text: i16) {
+        let layout = self.cache().grid.clone().unwrap_or_default();
+        let start_position = content_position.add_tuple((0, y_after_text));
+
+        for child in &self.children {
+            let mut child = child.borrow_mut();
+            if child.style.offset.is_absolute() {
+                // Use parent's 0,0 for absolutely relative children
+                child.calculate_canvas(content_position);
+            }
+        }
+
+        for &(i, area) in &layout.areas {
+            let Some(child) = self.children.get(i) else {
+                continue;
+            };
+
+            let (x, y) = layout.area_offset(area);
+            let position = start_position.add_tuple((x as i16, y as i16));
+            child.borrow_mut().calculate_canvas(position);
+        }
     }
 
     /// Render the node and its children to `canvas` within the given `viewport`. Node's canvas has to be
@@ -616,6 +1401,75 @@ impl Node {
             let child = child.borrow();
             child.render_to(viewport, canvas, hitmap);
         }
+
+        self.render_scrollbars(&viewport, canvas);
+    }
+
+    /// Renders the scrollbars over the last column and row of the content box, on top of the
+    /// children, within the content `viewport`
+    fn render_scrollbars(&self, viewport: &Viewport, canvas: &mut Canvas) {
+        if !self.style.scrollbar {
+            return;
+        }
+
+        let (max_x, max_y) = self.max_scroll();
+        let (overflow_x, overflow_y) = self.style.overflow;
+        let width = self.style.clamped_width();
+        let height = self.style.clamped_height();
+
+        let (x, y) = self.content_position();
+
+        let vertical = overflow_y.shows_scrollbar(max_y > 0) && width > 0;
+        let horizontal = overflow_x.shows_scrollbar(max_x > 0) && height > 0;
+
+        let thumb = Line::from_string("█");
+        if vertical {
+            let track = Line::from_string("│");
+            let (start, length) = Self::scrollbar_thumb(height, max_y, self.scroll.1);
+            let column = x + width as i16 - 1;
+
+            for i in 0..height {
+                let line = if (start..start + length).contains(&i) {
+                    &thumb
+                } else {
+                    &track
+                };
+                canvas.paste_on_top(line, (column, y + i as i16), viewport);
+            }
+        }
+
+        if horizontal {
+            let track = Line::from_string("─");
+            // Leave the corner to the vertical scrollbar
+            let width = width - vertical as u16;
+            let (start, length) = Self::scrollbar_thumb(width, max_x, self.scroll.0);
+            let row = y + height as i16 - 1;
+
+            for i in 0..width {
+                let line = if (start..start + length).contains(&i) {
+                    &thumb
+                } else {
+                    &track
+                };
+                canvas.paste_on_top(line, (x + i as i16, row), viewport);
+            }
+        }
+    }
+
+    /// Returns the `(start, length)` of a scrollbar thumb inside a `track` long scrollbar
+    fn scrollbar_thumb(track: u16, max_scroll: u16, scroll: u16) -> (u16, u16) {
+        let content = track as u32 + max_scroll as u32;
+        let length = (track as u32 * track as u32 / content.max(1)).clamp(1, track.max(1) as u32);
+        let length = length as u16;
+
+        let start = if max_scroll == 0 {
+            0
+        } else {
+            let free = track.saturating_sub(length) as u32;
+            (free * scroll.min(max_scroll) as u32 / max_scroll as u32) as u16
+        };
+
+        (start, length)
     }
 
     /// Handle a single event for this node. Returns wheter it should stop propagating
@@ -626,6 +1480,12 @@ impl Node {
             .handle(ctx, self, is_capturing)
     }
 
+    /// Returns true if any event handlers are registered on this node
+    #[inline]
+    pub fn has_handlers(&self) -> bool {
+        !self.handlers.borrow().is_empty()
+    }
+
     #[inline]
     pub fn add_handler<F: IntoEventHandler>(&mut self, handler: F, is_capturing: bool) {
         self.handlers
@@ -680,3 +1540,332 @@ impl Node {
         }
     }
 }
+
+#[cfg(test)]
+mod flex_tests {
+    use super::test_utils::{layout, node};
+    use super::*;
+    use crate::SizeValue;
+
+    fn container(width: u16, children: Vec<Node>) -> NodeHandle {
+        let mut root = node(SizeValue::cells(width), SizeValue::cells(1));
+        root.style.flex_row = true;
+        layout(root, children, (width, 1))
+    }
+
+    fn widths(root: &NodeHandle) -> Vec<u16> {
+        root.borrow()
+            .children
+            .iter()
+            .map(|c| c.borrow().style.total_width())
+            .collect()
+    }
+
+    #[test]
+    fn grow_fills_rest() {
+        let sidebar = node(SizeValue::cells(10), SizeValue::cells(1));
+        let mut main = node(SizeValue::auto(), SizeValue::cells(1));
+        main.style.flex_grow = 1;
+
+        let root = container(30, vec![sidebar, main]);
+        assert_eq!(widths(&root), [10, 20]);
+    }
+
+    #[test]
+    fn grow_proportional_with_gap() {
+        let mut a = node(SizeValue::cells(0), SizeValue::cells(1));
+        a.style.flex_grow = 1;
+        let mut b = node(SizeValue::cells(0), SizeValue::cells(1));
+        b.style.flex_grow = 2;
+
+        let mut root = node(SizeValue::cells(31), SizeValue::cells(1));
+        root.style.flex_row = true;
+        root.style.gap = (1, 0);
+        let root = layout(root, vec![a, b], (31, 1));
+        assert_eq!(widths(&root), [10, 20]);
+    }
+
+    #[test]
+    fn grow_respects_max_size() {
+        let mut a = node(SizeValue::auto(), SizeValue::cells(1));
+        a.style.flex_grow = 1;
+        a.style.max_size = Size::new(SizeValue::cells(5), SizeValue::auto());
+        let mut b = node(SizeValue::auto(), SizeValue::cells(1));
+        b.style.flex_grow = 1;
+
+        let root = container(20, vec![a, b]);
+        assert_eq!(widths(&root), [5, 15]);
+    }
+
+    #[test]
+    fn shrink_respects_min_size() {
+        let mut a = node(SizeValue::cells(20), SizeValue::cells(1));
+        a.style.flex_shrink = 1;
+        a.style.min_size = Size::new(SizeValue::cells(18), SizeValue::auto());
+        let mut b = node(SizeValue::cells(20), SizeValue::cells(1));
+        b.style.flex_shrink = 1;
+
+        let root = container(30, vec![a, b]);
+        assert_eq!(widths(&root), [18, 12]);
+    }
+
+    #[test]
+    fn shrink_weighted_by_size() {
+        let mut a = node(SizeValue::cells(20), SizeValue::cells(1));
+        a.style.flex_shrink = 1;
+        let mut b = node(SizeValue::cells(10), SizeValue::cells(1));
+        b.style.flex_shrink = 1;
+        let c = node(SizeValue::cells(5), SizeValue::cells(1));
+
+        let root = container(20, vec![a, b, c]);
+        assert_eq!(widths(&root), [10, 5, 5]);
+    }
+
+    #[test]
+    fn basis_then_grow() {
+        let mut a = node(SizeValue::cells(2), SizeValue::cells(1));
+        a.style.flex_basis = SizeValue::percent(50);
+        let mut b = node(SizeValue::auto(), SizeValue::cells(1));
+        b.style.flex_grow = 1;
+
+        let root = container(40, vec![a, b]);
+        assert_eq!(widths(&root), [20, 20]);
+    }
+
+    #[test]
+    fn no_flex_keeps_sizes() {
+        let a = node(SizeValue::cells(5), SizeValue::cells(1));
+        let b = node(SizeValue::cells(50), SizeValue::cells(1));
+
+        let root = container(20, vec![a, b]);
+        assert_eq!(widths(&root), [5, 50]);
+    }
+}
+
+#[cfg(test)]
+mod wrap_tests {
+    use super::test_utils::{item, layout, node, positions};
+    use super::*;
+    use crate::{Justify, SizeValue};
+
+    fn wrap_row(width: u16, gap: (u16, u16), justify: Justify, items: Vec<Node>) -> NodeHandle {
+        let mut root = node(SizeValue::cells(width), SizeValue::auto());
+        root.style.flex_row = true;
+        root.style.flex_wrap = true;
+        root.style.gap = gap;
+        root.style.justify = justify;
+        layout(root, items, (width, 100))
+    }
+
+    #[test]
+    fn breaks_lines() {
+        let items = vec![item(4, 1), item(4, 1), item(4, 1)];
+        let root = wrap_row(10, (1, 0), Justify::Start, items);
+
+        assert_eq!(positions(&root), [(0, 0), (5, 0), (0, 1)]);
+        assert_eq!(root.borrow().style.size.height.computed_size(), 2);
+    }
+
+    #[test]
+    fn line_height_and_row_gap() {
+        let items = vec![item(6, 2), item(6, 1), item(3, 1)];
+        let root = wrap_row(10, (0, 1), Justify::Start, items);
+
+        assert_eq!(positions(&root), [(0, 0), (0, 3), (6, 3)]);
+        assert_eq!(root.borrow().style.size.height.computed_size(), 4);
+    }
+
+    #[test]
+    fn justify_per_line() {
+        let items = vec![item(4, 1), item(4, 1), item(4, 1)];
+        let root = wrap_row(10, (0, 0), Justify::Center, items);
+
+        assert_eq!(positions(&root), [(1, 0), (5, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn grow_per_line() {
+        let mut grow = item(2, 1);
+        grow.style.flex_grow = 1;
+        let items = vec![item(6, 1), item(6, 1), grow];
+        let root = wrap_row(10, (0, 0), Justify::Start, items);
+
+        let widths = root
+            .borrow()
+            .children
+            .iter()
+            .map(|c| c.borrow().style.total_width())
+            .collect::<Vec<_>>();
+        assert_eq!(widths, [6, 6, 4]);
+        assert_eq!(positions(&root), [(0, 0), (0, 1), (6, 1)]);
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::test_utils::{layout, node};
+    use super::*;
+    use crate::{GridPlacement, SizeValue};
+
+    fn container(
+        width: SizeValue,
+        template: Grid,
+        gap: (u16, u16),
+        items: Vec<Node>,
+    ) -> NodeHandle {
+        let mut root = node(width, SizeValue::auto());
+        root.style.grid = Some(template);
+        root.style.gap = gap;
+        layout(root, items, (100, 100))
+    }
+
+    fn rects(root: &NodeHandle) -> Vec<(i16, i16, u16, u16)> {
+        root.borrow()
+            .children
+            .iter()
+            .map(|c| {
+                let c = c.borrow();
+                let (x, y) = c.absolute_position();
+                let (width, height) = c.style.total_size();
+                (x, y, width, height)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stretches_auto_items() {
+        let auto = || node(SizeValue::auto(), SizeValue::auto());
+        let items = vec![auto(), auto(), auto(), auto()];
+        let template = Grid::parse("10 1fr", "2 3").unwrap();
+        let root = container(SizeValue::cells(31), template, (1, 1), items);
+
+        assert_eq!(
+            rects(&root),
+            [(0, 0, 10, 2), (11, 0, 20, 2), (0, 3, 10, 3), (11, 3, 20, 3)]
+        );
+        assert_eq!(root.borrow().style.size.height.computed_size(), 6);
+    }
+
+    #[test]
+    fn auto_tracks_fit_items() {
+        let items = vec![
+            node(SizeValue::cells(4), SizeValue::cells(1)),
+            node(SizeValue::cells(7), SizeValue::cells(2)),
+            node(SizeValue::cells(2), SizeValue::cells(1)),
+        ];
+        let template = Grid::parse("auto auto", "").unwrap();
+        let root = container(SizeValue::auto(), template, (0, 0), items);
+
+        assert_eq!(rects(&root), [(0, 0, 4, 1), (4, 0, 7, 2), (0, 2, 2, 1)]);
+
+        let size = root.borrow().style.size;
+        assert_eq!(
+            (size.width.computed_size(), size.height.computed_size()),
+            (11, 3)
+        );
+    }
+
+    #[test]
+    fn explicit_placement_and_spans() {
+        let mut header = node(SizeValue::auto(), SizeValue::auto());
+        header.style.grid_placement = GridPlacement::auto().with_span(2, 1);
+        let mut side = node(SizeValue::auto(), SizeValue::auto());
+        side.style.grid_placement = GridPlacement::at(0, 1);
+        let main = node(SizeValue::auto(), SizeValue::auto());
+
+        let template = Grid::parse("5 1fr", "1 4").unwrap();
+        let root = container(
+            SizeValue::cells(20),
+            template,
+            (0, 0),
+            vec![header, main, side],
+        );
+
+        assert_eq!(rects(&root), [(0, 0, 20, 1), (5, 1, 15, 4), (0, 1, 5, 4)]);
+    }
+
+    #[test]
+    fn percent_tracks_and_items() {
+        let half = node(SizeValue::percent(50), SizeValue::cells(1));
+        let auto = node(SizeValue::auto(), SizeValue::cells(1));
+
+        let template = Grid::parse("25% 1fr", "").unwrap();
+        let root = container(SizeValue::cells(40), template, (0, 0), vec![half, auto]);
+
+        assert_eq!(rects(&root), [(0, 0, 5, 1), (10, 0, 30, 1)]);
+    }
+}
+
+#[cfg(test)]
+mod scroll_tests {
+    use super::test_utils::{item, layout, positions};
+    use super::*;
+    use crate::SizeValue;
+
+    fn container(overflow: Overflow, scroll: (u16, u16), items: Vec<Node>) -> NodeHandle {
+        let mut root = item(10, 5);
+        root.style.overflow = (overflow, overflow);
+        root.scroll = scroll;
+        layout(root, items, (100, 100))
+    }
+
+    #[test]
+    fn content_size_and_max_scroll() {
+        let root = container(Overflow::Auto, (0, 0), vec![item(12, 4), item(3, 4)]);
+
+        let root = root.borrow();
+        assert_eq!(root.cache().content_size, (12, 8));
+        assert_eq!(root.max_scroll(), (2, 3));
+    }
+
+    #[test]
+    fn scroll_offsets_children() {
+        let root = container(Overflow::Scroll, (1, 2), vec![item(12, 4), item(3, 4)]);
+        assert_eq!(positions(&root), [(-1, -2), (-1, 2)]);
+    }
+
+    #[test]
+    fn scroll_clamped_to_content() {
+        let root = container(Overflow::Auto, (5, 9), vec![item(12, 4), item(3, 4)]);
+
+        assert_eq!(root.borrow().scroll, (2, 3));
+        assert_eq!(positions(&root), [(-2, -3), (-2, 1)]);
+    }
+
+    #[test]
+    fn scroll_by_respects_overflow() {
+        let root = container(Overflow::Auto, (0, 0), vec![item(12, 8)]);
+        let mut node = root.borrow_mut();
+
+        assert!(node.scroll_by(1, 10));
+        assert_eq!(node.scroll, (1, 3));
+        assert!(!node.scroll_by(0, 1));
+
+        node.style.overflow.1 = Overflow::Hidden;
+        assert!(!node.scroll_by(0, -1));
+        assert!(node.scroll_by(-1, -1));
+        assert_eq!(node.scroll, (0, 3));
+    }
+
+    #[test]
+    fn auto_size_does_not_overflow() {
+        let mut root = Node::default();
+        root.style.size = Size::new(SizeValue::auto(), SizeValue::auto());
+        root.style.overflow = (Overflow::Auto, Overflow::Auto);
+
+        let root = root.into_handle();
+        root.add_child_node(item(12, 8));
+        root.borrow_mut()
+            .compute(Offset::default(), Size::from_cells(100, 100));
+
+        assert_eq!(root.borrow().max_scroll(), (0, 0));
+    }
+
+    #[test]
+    fn scrollbar_thumb() {
+        assert_eq!(Node::scrollbar_thumb(10, 0, 0), (0, 10));
+        assert_eq!(Node::scrollbar_thumb(10, 10, 0), (0, 5));
+        assert_eq!(Node::scrollbar_thumb(10, 10, 10), (5, 5));
+        assert_eq!(Node::scrollbar_thumb(4, 100, 50), (1, 1));
+    }
+}
@@ -0,0 +1,38 @@
+//! Fixtures shared by the layout tests
+
+use crate::{Node, NodeHandle, Offset, Size, SizeValue};
+
+/// Returns a node with the given size
+pub(crate) fn node(width: SizeValue, height: SizeValue) -> Node {
+    let mut node = Node::default();
+    node.style.size = Size::new(width, height);
+    node
+}
+
+/// Returns a node with a fixed size in cells
+pub(crate) fn item(width: u16, height: u16) -> Node {
+    node(SizeValue::cells(width), SizeValue::cells(height))
+}
+
+/// Adds `children` to `root` and computes its layout within `available` cells
+pub(crate) fn layout(root: Node, children: Vec<Node>, available: (u16, u16)) -> NodeHandle {
+    let root = root.into_handle();
+    for child in children {
+        root.add_child_node(child);
+    }
+
+    root.borrow_mut().compute(
+        Offset::default(),
+        Size::from_cells(available.0, available.1),
+    );
+    root
+}
+
+/// Returns the absolute positions of the children of `root`
+pub(crate) fn positions(root: &NodeHandle) -> Vec<(i16, i16)> {
+    root.borrow()
+        .children
+        .iter()
+        .map(|c| c.borrow().absolute_position())
+        .collect()
+}
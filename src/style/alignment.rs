@@ -1,5 +1,19 @@
 use crate::Style;
 
+/// Horizontal alignment of the text lines within the node's content width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    /// Start of the line
+    #[default]
+    Left,
+    /// Center of the line
+    Center,
+    /// End of the line
+    Right,
+    /// Words spread to fill the line, the last line of a paragraph is aligned left
+    Justify,
+}
+
 /// Justify content in the direction of the flex container
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Justify {
@@ -79,6 +93,19 @@ impl Justify {
 
         if flex_row { (offset, 0) } else { (0, offset) }
     }
+
+    /// Returns the extra spacing added after each item for the spaced variants, `0` otherwise.
+    #[inline(always)]
+    pub fn get_item_spacing(self, free_content_size: i16, item_count: usize) -> i16 {
+        let adjusted_item_count = match self {
+            Self::SpaceEvenly => item_count + 1, // adds an extra start offset
+            Self::SpaceBetween => item_count.saturating_sub(1), // between two items
+            Self::SpaceAround => item_count,
+            _ => return 0,
+        };
+
+        (free_content_size / adjusted_item_count.max(1) as i16).max(0)
+    }
 }
 
 /// Align items in the cross axis of the flex container
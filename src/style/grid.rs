@@ -0,0 +1,538 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Defines the size of a single grid track (a column or a row).
+pub enum Track {
+    /// Size in cells
+    Cells(u16),
+    /// Percentage of the grid's content size
+    Percent(u16),
+    /// Size determined by the largest item placed only in this track
+    #[default]
+    Auto,
+    /// Fraction of the free space left after sizing all other tracks
+    Fraction(u16),
+}
+
+impl Track {
+    /// Parses a string into a [`Track`]
+    /// Valid values are `auto, 50%, 42, 1fr`
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "auto" {
+            Some(Self::Auto)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse::<u16>().ok().map(Self::Percent)
+        } else if let Some(fraction) = value.strip_suffix("fr") {
+            fraction.parse::<u16>().ok().map(Self::Fraction)
+        } else {
+            value.parse::<u16>().ok().map(Self::Cells)
+        }
+    }
+
+    #[inline]
+    pub fn is_auto(self) -> bool {
+        matches!(self, Self::Auto)
+    }
+
+    #[inline]
+    pub fn is_fraction(self) -> bool {
+        matches!(self, Self::Fraction(_))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Grid template of a grid container. Children are placed into cells defined by the column and
+/// row tracks, rows are added implicitly when auto-placed children don't fit.
+pub struct Grid {
+    /// Column tracks, a grid has at least one column
+    pub columns: Vec<Track>,
+    /// Explicit row tracks
+    pub rows: Vec<Track>,
+    /// Track used for implicit rows
+    pub auto_rows: Track,
+}
+
+impl Grid {
+    /// Creates a new grid template with `auto` implicit rows
+    #[inline]
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            columns,
+            rows,
+            auto_rows: Track::Auto,
+        }
+    }
+
+    /// Parses whitespace separated track lists into a [`Grid`], e.g. `("20 1fr 2fr", "auto")`
+    pub fn parse(columns: &str, rows: &str) -> Option<Self> {
+        fn tracks(value: &str) -> Option<Vec<Track>> {
+            value.split_whitespace().map(Track::parse).collect()
+        }
+
+        Some(Self::new(tracks(columns)?, tracks(rows)?))
+    }
+
+    /// Sets the track used for implicit rows
+    #[inline]
+    #[must_use]
+    pub fn with_auto_rows(mut self, auto_rows: Track) -> Self {
+        self.auto_rows = auto_rows;
+        self
+    }
+
+    /// Returns the number of columns, at least `1`
+    #[inline]
+    pub fn column_count(&self) -> usize {
+        self.columns.len().max(1)
+    }
+
+    /// Places items on the grid. Items with both `column` and `row` set are placed first, the
+    /// rest are placed in order into the first free cells (row-major), after the previously
+    /// auto-placed item. Spans are clamped to the column count.
+    pub fn place_items(&self, placements: &[GridPlacement]) -> Vec<GridArea> {
+        let columns = self.column_count();
+        let mut occupied: Vec<Vec<bool>> = Vec::new();
+
+        let fits = |occupied: &Vec<Vec<bool>>, area: GridArea| {
+            (area.row..area.row + area.row_span).all(|row| {
+                (area.column..area.column + area.column_span).all(|column| {
+                    !occupied
+                        .get(row as usize)
+                        .is_some_and(|r| r[column as usize])
+                })
+            })
+        };
+
+        let occupy = |occupied: &mut Vec<Vec<bool>>, area: GridArea| {
+            let end_row = (area.row + area.row_span) as usize;
+            if occupied.len() < end_row {
+                occupied.resize(end_row, vec![false; columns]);
+            }
+
+            for row in area.row..area.row + area.row_span {
+                for column in area.column..area.column + area.column_span {
+                    occupied[row as usize][column as usize] = true;
+                }
+            }
+        };
+
+        let mut areas = vec![GridArea::default(); placements.len()];
+        let column_span = |p: &GridPlacement| p.column_span.clamp(1, columns as u16);
+
+        // Explicitly placed items
+        for (i, placement) in placements.iter().enumerate() {
+            let (Some(column), Some(row)) = (placement.column, placement.row) else {
+                continue;
+            };
+
+            let column_span = column_span(placement);
+            let column = column.min(columns as u16 - column_span);
+            let area = GridArea::new(column, row, column_span, placement.row_span.max(1));
+
+            occupy(&mut occupied, area);
+            areas[i] = area;
+        }
+
+        // Auto placed items, partially explicit items keep their fixed axis
+        let mut cursor = (0u16, 0u16);
+        for (i, placement) in placements.iter().enumerate() {
+            if placement.column.is_some() && placement.row.is_some() {
+                continue;
+            }
+
+            let column_span = column_span(placement);
+            let row_span = placement.row_span.max(1);
+            let last_column = columns as u16 - column_span;
+
+            let (mut column, mut row) = match (placement.column, placement.row) {
+                (Some(column), None) => (column.min(last_column), 0),
+                (None, Some(row)) => (0, row),
+                _ => cursor,
+            };
+
+            loop {
+                let area = GridArea::new(column, row, column_span, row_span);
+                if column <= last_column && fits(&occupied, area) {
+                    occupy(&mut occupied, area);
+                    areas[i] = area;
+                    break;
+                }
+
+                match (placement.column, placement.row) {
+                    (Some(_), None) => row += 1,
+                    (None, Some(_)) if column < last_column => column += 1,
+                    (None, Some(_)) => {
+                        // Row is full, fallback to the first free row
+                        column = 0;
+                        row += 1;
+                    }
+                    _ if column < last_column => column += 1,
+                    _ => {
+                        column = 0;
+                        row += 1;
+                    }
+                }
+            }
+
+            if placement.column.is_none() && placement.row.is_none() {
+                cursor = (areas[i].column + column_span, areas[i].row);
+                if cursor.0 >= columns as u16 {
+                    cursor = (0, cursor.1 + 1);
+                }
+            }
+        }
+
+        areas
+    }
+
+    /// Returns the row tracks, extended with implicit rows up to `count`
+    pub fn row_tracks(&self, count: usize) -> Vec<Track> {
+        let mut rows = self.rows.clone();
+        if rows.len() < count {
+            rows.resize(count, self.auto_rows);
+        }
+        rows
+    }
+
+    /// Returns the column tracks, a grid without columns has a single `auto` column
+    pub fn column_tracks(&self) -> Vec<Track> {
+        if self.columns.is_empty() {
+            vec![Track::Auto]
+        } else {
+            self.columns.clone()
+        }
+    }
+}
+
+/// Resolves the sizes of `tracks` along one axis.
+///
+/// - `content_size` is the grid's content size along the axis, `None` if it's auto-sized, in
+///   which case percentage and fraction tracks are sized like `auto` tracks.
+/// - `items` are `(start, span, size)` of each item along the axis.
+///
+/// Auto tracks fit their largest single-span item, items spanning multiple tracks grow the auto
+/// tracks they span evenly if they don't fit.
+pub(crate) fn resolve_tracks(
+    tracks: &[Track],
+    content_size: Option<u16>,
+    gap: u16,
+    items: &[(u16, u16, u16)],
+) -> Vec<u16> {
+    let is_auto = |track: Track| match track {
+        Track::Auto => true,
+        Track::Percent(_) | Track::Fraction(_) => content_size.is_none(),
+        Track::Cells(_) => false,
+    };
+
+    let mut sizes = tracks
+        .iter()
+        .map(|&track| match (track, content_size) {
+            (Track::Cells(cells), _) => cells,
+            (Track::Percent(p), Some(size)) => (size as u32 * p as u32 / 100) as u16,
+            _ => 0,
+        })
+        .collect::<Vec<_>>();
+
+    // Fit single-span items into auto tracks
+    for &(start, span, size) in items {
+        let start = start as usize;
+        if span == 1 && tracks.get(start).is_some_and(|&t| is_auto(t)) {
+            sizes[start] = sizes[start].max(size);
+        }
+    }
+
+    // Grow auto tracks for multi-span items
+    for &(start, span, size) in items.iter().filter(|item| item.1 > 1) {
+        let range = start as usize..(start + span).min(tracks.len() as u16) as usize;
+        let gaps = gap * (range.len() as u16).saturating_sub(1);
+        let spanned = sizes[range.clone()].iter().sum::<u16>() + gaps;
+
+        let auto_tracks = range.filter(|&i| is_auto(tracks[i])).collect::<Vec<_>>();
+        if spanned >= size || auto_tracks.is_empty() {
+            continue;
+        }
+
+        let extra = size - spanned;
+        let share = extra / auto_tracks.len() as u16;
+        let remainder = extra % auto_tracks.len() as u16;
+        for (n, &i) in auto_tracks.iter().enumerate() {
+            sizes[i] += share + (n < remainder as usize) as u16;
+        }
+    }
+
+    // Distribute the free space between fraction tracks
+    if let Some(content_size) = content_size {
+        let fractions = tracks
+            .iter()
+            .map(|&t| match t {
+                Track::Fraction(fr) => fr as u32,
+                _ => 0,
+            })
+            .collect::<Vec<_>>();
+        let total_fraction = fractions.iter().sum::<u32>();
+
+        let gaps = gap * (tracks.len() as u16).saturating_sub(1);
+        let used = sizes
+            .iter()
+            .fold(gaps, |acc, size| acc.saturating_add(*size));
+        let free = content_size.saturating_sub(used) as u32;
+
+        // Non-fraction tracks are skipped, so there is no division when the total is `0`
+        let mut remainder = free;
+        for (size, fraction) in sizes.iter_mut().zip(&fractions) {
+            if *fraction == 0 {
+                continue;
+            }
+
+            let share = free * fraction / total_fraction;
+            *size = share as u16;
+            remainder -= share;
+        }
+
+        // Distribute the remainder one cell at a time
+        for (size, fraction) in sizes.iter_mut().zip(&fractions) {
+            if remainder == 0 {
+                break;
+            }
+            if *fraction > 0 {
+                *size += 1;
+                remainder -= 1;
+            }
+        }
+    }
+
+    sizes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Placement of a grid item inside its grid container. Unset `column` or `row` are auto placed.
+pub struct GridPlacement {
+    /// Column start index
+    pub column: Option<u16>,
+    /// Row start index
+    pub row: Option<u16>,
+    /// Number of spanned columns
+    pub column_span: u16,
+    /// Number of spanned rows
+    pub row_span: u16,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self::auto()
+    }
+}
+
+impl GridPlacement {
+    /// Auto placed item spanning a single cell
+    #[inline]
+    pub fn auto() -> Self {
+        Self {
+            column: None,
+            row: None,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+
+    /// Item placed at `column` and `row`
+    #[inline]
+    pub fn at(column: u16, row: u16) -> Self {
+        Self {
+            column: Some(column),
+            row: Some(row),
+            ..Self::auto()
+        }
+    }
+
+    /// Sets the column and row spans
+    #[inline]
+    #[must_use]
+    pub fn with_span(mut self, column_span: u16, row_span: u16) -> Self {
+        self.column_span = column_span;
+        self.row_span = row_span;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Resolved grid area of an item
+pub struct GridArea {
+    pub column: u16,
+    pub row: u16,
+    pub column_span: u16,
+    pub row_span: u16,
+}
+
+impl GridArea {
+    #[inline]
+    pub fn new(column: u16, row: u16, column_span: u16, row_span: u16) -> Self {
+        Self {
+            column,
+            row,
+            column_span,
+            row_span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Resolved layout of a grid container
+pub struct GridLayout {
+    /// Resolved column sizes
+    pub columns: Vec<u16>,
+    /// Resolved row sizes, including implicit rows
+    pub rows: Vec<u16>,
+    /// Child index and its resolved area, for each relative child
+    pub areas: Vec<(usize, GridArea)>,
+    /// Column and row gap
+    pub gap: (u16, u16),
+}
+
+impl GridLayout {
+    /// Returns the `(x, y)` offset of `area` from the start of the grid
+    pub fn area_offset(&self, area: GridArea) -> (u16, u16) {
+        let offset = |tracks: &[u16], start: u16, gap: u16| {
+            tracks
+                .iter()
+                .take(start as usize)
+                .fold(0u16, |acc, size| acc.saturating_add(size + gap))
+        };
+
+        (
+            offset(&self.columns, area.column, self.gap.0),
+            offset(&self.rows, area.row, self.gap.1),
+        )
+    }
+
+    /// Returns the `(width, height)` of `area`, including the gaps between spanned tracks
+    pub fn area_size(&self, area: GridArea) -> (u16, u16) {
+        let size = |tracks: &[u16], start: u16, span: u16, gap: u16| {
+            let spanned = tracks.iter().skip(start as usize).take(span as usize);
+            let gaps = gap * (spanned.len() as u16).saturating_sub(1);
+            spanned.fold(gaps, |acc, size| acc.saturating_add(*size))
+        };
+
+        (
+            size(&self.columns, area.column, area.column_span, self.gap.0),
+            size(&self.rows, area.row, area.row_span, self.gap.1),
+        )
+    }
+
+    /// Returns the total `(width, height)` of the grid, including gaps
+    pub fn size(&self) -> (u16, u16) {
+        let size = |tracks: &[u16], gap: u16| {
+            let gaps = gap * (tracks.len() as u16).saturating_sub(1);
+            tracks
+                .iter()
+                .fold(gaps, |acc, size| acc.saturating_add(*size))
+        };
+
+        (
+            size(&self.columns, self.gap.0),
+            size(&self.rows, self.gap.1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::*;
+
+    #[test]
+    fn parse_tracks() {
+        let grid = Grid::parse("20 1fr 2fr 50%", "auto 3").unwrap();
+        assert_eq!(
+            grid.columns,
+            [
+                Track::Cells(20),
+                Track::Fraction(1),
+                Track::Fraction(2),
+                Track::Percent(50)
+            ]
+        );
+        assert_eq!(grid.rows, [Track::Auto, Track::Cells(3)]);
+        assert_eq!(Grid::parse("1x", ""), None);
+    }
+
+    #[test]
+    fn fixed_and_fractions() {
+        let tracks = [Track::Cells(10), Track::Fraction(1), Track::Fraction(2)];
+        assert_eq!(resolve_tracks(&tracks, Some(41), 1, &[]), [10, 10, 19]);
+    }
+
+    #[test]
+    fn auto_fits_items() {
+        let tracks = [Track::Auto, Track::Fraction(1)];
+        let items = [(0, 1, 6), (0, 1, 8), (1, 1, 3)];
+        assert_eq!(resolve_tracks(&tracks, Some(20), 0, &items), [8, 12]);
+
+        // Auto sized grid
+        assert_eq!(resolve_tracks(&tracks, None, 0, &items), [8, 3]);
+    }
+
+    #[test]
+    fn spanning_item_grows_auto_tracks() {
+        let tracks = [Track::Auto, Track::Auto, Track::Cells(4)];
+        let items = [(0, 1, 2), (0, 3, 15)];
+        assert_eq!(resolve_tracks(&tracks, None, 1, &items), [6, 3, 4]);
+    }
+
+    #[test]
+    fn auto_placement() {
+        let grid = Grid::parse("1 1 1", "").unwrap();
+        let placements = [
+            GridPlacement::auto(),
+            GridPlacement::auto().with_span(2, 1),
+            GridPlacement::auto().with_span(2, 1),
+            GridPlacement::at(2, 1),
+        ];
+
+        assert_eq!(
+            grid.place_items(&placements),
+            [
+                GridArea::new(0, 0, 1, 1),
+                GridArea::new(1, 0, 2, 1),
+                GridArea::new(0, 1, 2, 1),
+                GridArea::new(2, 1, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn placement_skips_occupied() {
+        let grid = Grid::parse("1 1", "").unwrap();
+        let placements = [
+            GridPlacement::at(0, 0).with_span(1, 2),
+            GridPlacement::auto(),
+            GridPlacement::auto(),
+            GridPlacement {
+                column: Some(0),
+                ..GridPlacement::auto()
+            },
+        ];
+
+        assert_eq!(
+            grid.place_items(&placements),
+            [
+                GridArea::new(0, 0, 1, 2),
+                GridArea::new(1, 0, 1, 1),
+                GridArea::new(1, 1, 1, 1),
+                GridArea::new(0, 2, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn layout_offsets() {
+        let layout = GridLayout {
+            columns: vec![10, 5, 5],
+            rows: vec![1, 2],
+            areas: vec![],
+            gap: (1, 1),
+        };
+
+        let area = GridArea::new(1, 1, 2, 1);
+        assert_eq!(layout.area_offset(area), (11, 2));
+        assert_eq!(layout.area_size(area), (11, 2));
+        assert_eq!(layout.size(), (22, 4));
+    }
+}
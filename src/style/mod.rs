@@ -1,11 +1,16 @@
 mod alignment;
 pub mod border;
+mod grid;
 mod offset;
+mod overflow;
 mod padding;
 mod size;
 
-pub use alignment::{Align, Justify};
+pub use alignment::{Align, Justify, TextAlign};
+pub(crate) use grid::resolve_tracks;
+pub use grid::{Grid, GridArea, GridLayout, GridPlacement, Track};
 pub use offset::Offset;
+pub use overflow::{Overflow, TextOverflow};
 pub use padding::Padding;
 pub use size::{Size, SizeValue};
 
@@ -30,15 +35,40 @@ pub struct Style {
     pub underline: bool,
     pub dim: bool,
     pub crossed: bool,
+    /// Horizontal alignment of the text lines
+    pub text_align: TextAlign,
+    /// Truncation of the text lines overflowing the content width
+    pub text_overflow: TextOverflow,
+    /// Maximum number of visible text lines, the rest of the text is hidden
+    pub max_lines: Option<u16>,
 
     pub padding: Padding,
     pub border: Border,
 
+    /// Overflow behavior of the content along the `(x, y)` axes
+    pub overflow: (Overflow, Overflow),
+    /// Renders scrollbars on the scrollable axes, see [`Overflow`]
+    pub scrollbar: bool,
+
     pub justify: Justify,
     pub align: Align,
     pub flex_row: bool,
-    // pub grow: bool,
+    /// Wraps children onto new lines (or columns) when they overflow the main axis
+    pub flex_wrap: bool,
+    /// Share of the free main axis space this node takes as a flex child, `0` disables growing
+    pub flex_grow: u16,
+    /// Share of the overflowing main axis space this node gives up as a flex child, weighted by
+    /// its base size. `0` disables shrinking
+    pub flex_shrink: u16,
+    /// Initial main axis size of this node as a flex child, before growing or shrinking. `Auto`
+    /// uses the node's size
+    pub flex_basis: SizeValue,
     pub gap: (u16, u16),
+
+    /// Lays out children on a grid instead of a flexbox, the gap is used between tracks
+    pub grid: Option<Grid>,
+    /// Placement of this node as a grid item
+    pub grid_placement: GridPlacement,
 }
 
 impl Style {
@@ -100,11 +130,12 @@ impl Style {
 
         // Finalize and wrap text
         let self_width = self.size.width.computed_size();
-        let line_wrap_len_diff = text.wrap_text(self_width);
+        let text_height = self.text_size(text).1;
+        text.wrap_text(self_width);
 
         // Recalculate height with wrapped text height, if height is auto
         if self.size.height.is_auto() {
-            let text_height_diff = line_wrap_len_diff.min(u16::MAX as usize) as u16;
+            let text_height_diff = self.text_size(text).1.saturating_sub(text_height);
 
             // Add text height wrap difference to the current size
             let height = self.size.height.computed_size();
@@ -141,7 +172,7 @@ impl Style {
         } = self.size.compute_size(parent_size, (0, 0));
 
         // Get text dimensions
-        let (text_width, text_height) = text.get_visual_size();
+        let (text_width, text_height) = self.text_size(text);
 
         // Set to intrinsic text size if auto size
         if width.is_auto() {
@@ -155,6 +186,12 @@ impl Style {
         self.size = Size::new(width, height);
     }
 
+    /// Returns the visual size of `text`, with its height clamped to [`Style::max_lines`]
+    pub fn text_size(&self, text: &Text) -> (u16, u16) {
+        let (width, height) = text.get_visual_size();
+        (width, self.max_lines.map_or(height, |max| height.min(max)))
+    }
+
     /// Returns the extra width (horizontal padding and borders)
     #[inline]
     pub fn extra_width(&self) -> u16 {
@@ -200,6 +237,18 @@ impl Style {
     pub fn total_size(&self) -> (u16, u16) {
         (self.total_width(), self.total_height())
     }
+
+    /// True if the node's main axis size depends on its parent's flex layout
+    #[inline]
+    pub fn is_flexible(&self) -> bool {
+        self.flex_grow > 0 || self.flex_shrink > 0 || !self.flex_basis.is_auto()
+    }
+
+    /// Returns true if the content can be scrolled by the user along any axis
+    #[inline]
+    pub fn is_scrollable(&self) -> bool {
+        self.overflow.0.is_scrollable() || self.overflow.1.is_scrollable()
+    }
 }
 
 impl Size {
@@ -0,0 +1,54 @@
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Defines how the content overflowing a node is handled along an axis.
+pub enum Overflow {
+    /// Overflowing content is clipped and can't be scrolled by the user
+    #[default]
+    Hidden,
+    /// Content can be scrolled, the scrollbar is always shown
+    Scroll,
+    /// Content can be scrolled, the scrollbar is shown only if the content overflows
+    Auto,
+}
+
+impl Overflow {
+    /// Returns true if the content can be scrolled by the user
+    #[inline]
+    pub fn is_scrollable(self) -> bool {
+        !matches!(self, Self::Hidden)
+    }
+
+    /// Returns true if the scrollbar should be shown, `overflowing` is whether the content
+    /// overflows along the axis
+    #[inline]
+    pub fn shows_scrollbar(self, overflowing: bool) -> bool {
+        match self {
+            Self::Hidden => false,
+            Self::Scroll => true,
+            Self::Auto => overflowing,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Defines how text lines overflowing the node's content width are truncated. Any ellipsis mode
+/// also adds an ellipsis to the end of the last line of text clamped by
+/// [`max_lines`](crate::Style::max_lines), since the hidden lines always follow it.
+pub enum TextOverflow {
+    /// Lines are cut at the content width
+    #[default]
+    Clip,
+    /// Lines are truncated at the start, `…ain.rs`
+    EllipsisStart,
+    /// Lines are truncated in the middle, `/ho…in.rs`
+    EllipsisMiddle,
+    /// Lines are truncated at the end, `/home/…`
+    EllipsisEnd,
+}
+
+impl TextOverflow {
+    /// Returns true if the overflow is marked with an ellipsis
+    #[inline]
+    pub fn is_ellipsis(self) -> bool {
+        !matches!(self, Self::Clip)
+    }
+}
@@ -0,0 +1,336 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crossterm::style::{Attribute, Color};
+use unicode_width::UnicodeWidthChar;
+
+use crate::code::CodeUnit;
+
+use super::{ByteSpan, Text, into_style_spans};
+
+/// Columns between tab stops
+const TAB_WIDTH: usize = 8;
+
+impl Text {
+    /// Creates a text object from `input` containing ANSI escape sequences, like the output of
+    /// `cargo`, `git` or test runners.
+    ///
+    /// SGR sequences (`ESC[...m`) with 16, 256 and truecolor colors, attributes and resets are
+    /// converted into styles. Other escape sequences (cursor movement, OSC, DCS, ...) and control
+    /// characters are stripped, tabs are expanded to spaces.
+    pub fn from_ansi(input: &str) -> Self {
+        let mut plain = String::new();
+        let mut spans = Vec::<ByteSpan>::new();
+        let mut state = CodeUnit::new();
+        let mut column = 0;
+        let mut chars = input.char_indices().peekable();
+
+        // Starts a new span at the end of the plain text if the state changed
+        let mut set_state = |state: &mut CodeUnit, new: CodeUnit, plain: &str| {
+            if *state == new {
+                return;
+            }
+            *state = new;
+
+            if let Some(last) = spans.last_mut() {
+                last.end = last.end.min(plain.len());
+            }
+            spans.push(ByteSpan {
+                codes: new.into_codes(),
+                start: plain.len(),
+                end: usize::MAX,
+            });
+        };
+
+        while let Some((_, char)) = chars.next() {
+            match char {
+                '\x1b' => match chars.next().map(|(_, c)| c) {
+                    Some('[') => {
+                        if let Some(new) = parse_csi(&mut chars, state) {
+                            set_state(&mut state, new, &plain);
+                        }
+                    }
+                    // Strings terminated by `BEL` or `ST`: OSC, DCS, SOS, PM and APC
+                    Some(']' | 'P' | 'X' | '^' | '_') => skip_string(&mut chars),
+                    // Intermediate bytes followed by a final byte, like `ESC ( B`
+                    Some('\x20'..='\x2f') => {
+                        while chars
+                            .next_if(|&(_, c)| matches!(c, '\x20'..='\x2f'))
+                            .is_some()
+                        {}
+                        chars.next();
+                    }
+                    // Other escape sequences are two characters long
+                    _ => {}
+                },
+                // C1 control sequence introducer
+                '\u{9b}' => {
+                    if let Some(new) = parse_csi(&mut chars, state) {
+                        set_state(&mut state, new, &plain);
+                    }
+                }
+                '\n' => {
+                    plain.push('\n');
+                    column = 0;
+                }
+                '\t' => {
+                    let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                    plain.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                }
+                _ if char.is_control() => {}
+                _ => {
+                    plain.push(char);
+                    column += char.width().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut text = Text::plain(&plain);
+        let spans = spans.into_iter().filter(|s| !s.codes.is_empty()).collect();
+        let styles = into_style_spans(&plain, &text.input, spans);
+        text.add_styles(styles);
+
+        text
+    }
+}
+
+/// Skips a string terminated by `BEL` or `ESC \`
+fn skip_string(chars: &mut Peekable<CharIndices>) {
+    while let Some((_, char)) = chars.next() {
+        match char {
+            '\x07' | '\u{9c}' => return,
+            '\x1b' if chars.next_if(|&(_, c)| c == '\\').is_some() => return,
+            _ => {}
+        }
+    }
+}
+
+/// Parses a control sequence after its introducer. Returns the new state if it was an SGR
+/// sequence, other sequences are skipped.
+fn parse_csi(chars: &mut Peekable<CharIndices>, state: CodeUnit) -> Option<CodeUnit> {
+    let mut params = String::new();
+
+    // Parameter and intermediate bytes, up to the final byte
+    for (_, char) in chars.by_ref() {
+        match char {
+            '\x30'..='\x3f' | '\x20'..='\x2f' => params.push(char),
+            'm' => return Some(apply_sgr(&params, state)),
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Applies the SGR `params` to `state`, unknown parameters are ignored
+fn apply_sgr(params: &str, mut state: CodeUnit) -> CodeUnit {
+    // Parameters separated by `;`, each can have `:` separated sub parameters
+    let mut params = params.split(';').map(|param| {
+        param
+            .split(':')
+            .map(|p| p.parse::<u16>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    });
+
+    while let Some(param) = params.next() {
+        let code = param[0];
+
+        // Extended colors take their arguments from sub parameters or the next parameters
+        let mut extended_color = || -> Option<Color> {
+            let args = if param.len() > 1 {
+                param[1..].to_vec()
+            } else {
+                let mode = params.next()?.first().copied()?;
+                let count = if mode == 2 { 3 } else { 1 };
+                std::iter::once(mode)
+                    .chain((0..count).filter_map(|_| params.next()?.first().copied()))
+                    .collect()
+            };
+
+            let byte = |v: u16| u8::try_from(v).ok();
+            match args.as_slice() {
+                [5, n] => Some(Color::AnsiValue(byte(*n)?)),
+                // Colon form may contain a color space id
+                [2, r, g, b] | [2, _, r, g, b, ..] => Some(Color::Rgb {
+                    r: byte(*r)?,
+                    g: byte(*g)?,
+                    b: byte(*b)?,
+                }),
+                _ => None,
+            }
+        };
+
+        match code {
+            0 => state = CodeUnit::new(),
+            1 => state.apply_attr(Attribute::Bold),
+            2 => state.apply_attr(Attribute::Dim),
+            3 => state.apply_attr(Attribute::Italic),
+            4 => state.apply_attr(match param.get(1) {
+                Some(0) => Attribute::NoUnderline,
+                Some(2) => Attribute::DoubleUnderlined,
+                Some(3) => Attribute::Undercurled,
+                Some(4) => Attribute::Underdotted,
+                Some(5) => Attribute::Underdashed,
+                _ => Attribute::Underlined,
+            }),
+            5 => state.apply_attr(Attribute::SlowBlink),
+            6 => state.apply_attr(Attribute::RapidBlink),
+            7 => state.apply_attr(Attribute::Reverse),
+            8 => state.apply_attr(Attribute::Hidden),
+            9 => state.apply_attr(Attribute::CrossedOut),
+            21 => state.apply_attr(Attribute::DoubleUnderlined),
+            22 => state.apply_attr(Attribute::NormalIntensity),
+            23 => state.apply_attr(Attribute::NoItalic),
+            24 => state.apply_attr(Attribute::NoUnderline),
+            25 => state.apply_attr(Attribute::NoBlink),
+            27 => state.apply_attr(Attribute::NoReverse),
+            28 => state.apply_attr(Attribute::NoHidden),
+            29 => state.apply_attr(Attribute::NotCrossedOut),
+            53 => state.apply_attr(Attribute::OverLined),
+            55 => state.apply_attr(Attribute::NotOverLined),
+            30..=37 => state.set_fg(standard_color(code - 30)),
+            90..=97 => state.set_fg(standard_color(code - 90 + 8)),
+            40..=47 => state.set_bg(standard_color(code - 40)),
+            100..=107 => state.set_bg(standard_color(code - 100 + 8)),
+            38 => {
+                if let Some(color) = extended_color() {
+                    state.set_fg(color);
+                }
+            }
+            48 => {
+                if let Some(color) = extended_color() {
+                    state.set_bg(color);
+                }
+            }
+            58 => {
+                if let Some(color) = extended_color() {
+                    state.set_underline_color(color);
+                }
+            }
+            59 => state.set_underline_color(Color::Reset),
+            39 => state.set_fg(Color::Reset),
+            49 => state.set_bg(Color::Reset),
+            _ => {}
+        }
+    }
+
+    state
+}
+
+/// Returns one of the 16 standard colors, `8..16` are the bright colors
+fn standard_color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::DarkRed,
+        2 => Color::DarkGreen,
+        3 => Color::DarkYellow,
+        4 => Color::DarkBlue,
+        5 => Color::DarkMagenta,
+        6 => Color::DarkCyan,
+        7 => Color::Grey,
+        8 => Color::DarkGrey,
+        9 => Color::Red,
+        10 => Color::Green,
+        11 => Color::Yellow,
+        12 => Color::Blue,
+        13 => Color::Magenta,
+        14 => Color::Cyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod ansi_tests {
+    use crate::Code;
+
+    use super::*;
+
+    fn spans(text: &Text) -> Vec<(Code, usize, usize, usize)> {
+        let mut spans = text
+            .styles
+            .iter()
+            .map(|s| (s.code, s.line, s.character, s.length))
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|s| (s.1, s.2));
+        spans
+    }
+
+    fn content(text: &Text) -> Vec<&str> {
+        text.input.iter().map(|l| l.content()).collect()
+    }
+
+    #[test]
+    fn cargo_output() {
+        let text = Text::from_ansi("\x1b[0m\x1b[1m\x1b[32m   Compiling\x1b[0m tui\n");
+        assert_eq!(content(&text), ["   Compiling tui", ""]);
+        assert_eq!(
+            spans(&text),
+            [
+                (Code::Foreground(Color::DarkGreen), 0, 0, 12),
+                (Code::Attribute(Attribute::Bold), 0, 0, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn extended_colors() {
+        let rgb = Color::Rgb { r: 1, g: 2, b: 3 };
+        let text = Text::from_ansi("\x1b[38;5;208ma\x1b[48;2;1;2;3mb\x1b[39;49;38:2::1:2:3mc");
+        assert_eq!(
+            spans(&text),
+            [
+                (Code::Foreground(Color::AnsiValue(208)), 0, 0, 2),
+                (Code::Background(rgb), 0, 1, 1),
+                (Code::Foreground(rgb), 0, 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn resets_and_bright_colors() {
+        let text = Text::from_ansi("\x1b[91;4ma\x1b[24mb\x1b[mc\x1b[4:3md");
+        assert_eq!(
+            spans(&text),
+            [
+                (Code::Attribute(Attribute::Underlined), 0, 0, 1),
+                (Code::Foreground(Color::Red), 0, 0, 2),
+                (Code::Attribute(Attribute::Undercurled), 0, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn underline_color() {
+        let red = Color::Rgb { r: 255, g: 0, b: 0 };
+        let text = Text::from_ansi("\x1b[4:3;58;2;255;0;0mab\x1b[59mc\x1b[58:5:4md");
+        assert_eq!(
+            spans(&text),
+            [
+                (Code::UnderlineColor(red), 0, 0, 2),
+                (Code::Attribute(Attribute::Undercurled), 0, 0, 4),
+                (Code::UnderlineColor(Color::AnsiValue(4)), 0, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_across_lines() {
+        let text = Text::from_ansi("\x1b[31mab\ncd\x1b[0m");
+        let red = Code::Foreground(Color::DarkRed);
+        assert_eq!(spans(&text), [(red, 0, 0, 2), (red, 1, 0, 2)]);
+    }
+
+    #[test]
+    fn strips_other_sequences() {
+        let input = "\x1b]8;;http://a.b\x1b\\link\x1b]8;;\x07 \x1b[2K\x1b[1Gok\r\x1b(B\x07!";
+        let text = Text::from_ansi(input);
+        assert_eq!(content(&text), ["link ok!"]);
+        assert!(text.styles.is_empty());
+    }
+
+    #[test]
+    fn expands_tabs() {
+        let text = Text::from_ansi("a\tb\n\x1b[1m漢\x1b[0m\tc");
+        assert_eq!(content(&text), ["a       b", "漢      c"]);
+    }
+}
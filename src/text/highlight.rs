@@ -0,0 +1,585 @@
+use std::{fmt::Debug, ops::Range};
+
+use crossterm::style::Color;
+
+use crate::Code;
+
+use super::{BufferLine, StyleSpan, Text};
+
+/// Produces syntax highlighting [`StyleSpan`]s for [`Text::input`] lines, see
+/// [`Text::set_highlighter`].
+///
+/// Lines are highlighted one by one, each line starts in the state left by the previous line,
+/// which allows constructs like block comments to span multiple lines. When lines are edited,
+/// only the edited lines and the following lines whose start state has changed are highlighted
+/// again.
+pub trait Highlighter {
+    /// Returns the spans of `line` at `line_index`. `state` is the state at the end of the
+    /// previous line (`0` for the first line), and has to be updated to the state at the end of
+    /// `line`. The state is opaque to the text.
+    fn highlight_line(
+        &mut self,
+        line: &BufferLine,
+        line_index: usize,
+        state: &mut u32,
+    ) -> Vec<StyleSpan>;
+
+    /// Returns the spans of all `lines`
+    fn highlight(&mut self, lines: &[BufferLine]) -> Vec<StyleSpan> {
+        let mut state = 0;
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| self.highlight_line(line, i, &mut state))
+            .collect()
+    }
+}
+
+impl<H: Highlighter + ?Sized> Highlighter for Box<H> {
+    fn highlight_line(
+        &mut self,
+        line: &BufferLine,
+        line_index: usize,
+        state: &mut u32,
+    ) -> Vec<StyleSpan> {
+        (**self).highlight_line(line, line_index, state)
+    }
+}
+
+/// Highlighting state of a [`Text`]
+pub(super) struct Highlighting {
+    highlighter: Box<dyn Highlighter>,
+    /// Spans of each input line
+    spans: Vec<Vec<StyleSpan>>,
+    /// Highlighter state at the end of each input line
+    states: Vec<u32>,
+}
+
+impl Debug for Highlighting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Highlighting")
+            .field("spans", &self.spans.len())
+            .field("states", &self.states)
+            .finish()
+    }
+}
+
+impl Text {
+    /// Sets the syntax highlighter, highlights all input lines and re-prepares the text. Styles in
+    /// [`Text::styles`] take precedence over the highlighting.
+    pub fn set_highlighter(&mut self, highlighter: impl Highlighter + 'static) {
+        self.highlighting = Some(Highlighting {
+            highlighter: Box::new(highlighter),
+            spans: Vec::new(),
+            states: Vec::new(),
+        });
+
+        self.highlight_edit(0, 0, self.input.len());
+        self.prepare_text(u16::MAX);
+    }
+
+    /// Removes the syntax highlighter and its styles, and re-prepares the text
+    pub fn remove_highlighter(&mut self) -> Option<Box<dyn Highlighter>> {
+        let highlighting = self.highlighting.take()?;
+        self.prepare_text(u16::MAX);
+        Some(highlighting.highlighter)
+    }
+
+    /// Highlights the input after `removed` lines starting at `start` have been replaced by
+    /// `inserted` lines. The text has to be prepared again afterwards, [`Text::replace_lines`]
+    /// does both.
+    ///
+    /// Lines after the edit are highlighted only if the edited lines end in a different state than
+    /// before, until a line ends in the same state as before. Returns the range of the lines
+    /// highlighted again.
+    pub fn highlight_edit(
+        &mut self,
+        start: usize,
+        removed: usize,
+        inserted: usize,
+    ) -> Range<usize> {
+        let Some(highlighting) = &mut self.highlighting else {
+            return start..start;
+        };
+        let Highlighting {
+            highlighter,
+            spans,
+            states,
+        } = highlighting;
+
+        let end = start.saturating_add(removed).min(spans.len());
+        let start = start.min(end);
+        // State at the end of the replaced lines, which the following lines start in
+        let boundary_state = end.checked_sub(1).map_or(0, |i| states[i]);
+        spans.splice(start..end, std::iter::repeat_n(Vec::new(), inserted));
+        states.splice(start..end, std::iter::repeat_n(0, inserted));
+
+        // The input was changed without reporting the edit, highlight everything
+        let (start, inserted) = if spans.len() != self.input.len() {
+            *spans = vec![Vec::new(); self.input.len()];
+            *states = vec![0; self.input.len()];
+            (0, self.input.len())
+        } else {
+            (start, inserted)
+        };
+
+        let mut state = start.checked_sub(1).map_or(0, |i| states[i]);
+        if inserted == 0 && state == boundary_state {
+            return start..start;
+        }
+        let mut highlighted = start;
+        for (i, line) in self.input.iter().enumerate().skip(start) {
+            let old_state = if i + 1 == start + inserted {
+                boundary_state
+            } else {
+                states[i]
+            };
+            spans[i] = highlighter.highlight_line(line, i, &mut state);
+            states[i] = state;
+            highlighted = i + 1;
+
+            if i + 1 >= start + inserted && state == old_state {
+                break;
+            }
+        }
+        start..highlighted
+    }
+
+    /// Replaces the input lines in `range` with `lines`, highlights them and re-prepares the text
+    pub fn replace_lines(&mut self, range: Range<usize>, lines: Vec<BufferLine>) {
+        let (start, removed, inserted) = (range.start, range.len(), lines.len());
+
+        self.input.splice(range, lines);
+        self.highlight_edit(start, removed, inserted);
+        self.prepare_text(u16::MAX);
+    }
+
+    /// Returns the flattened highlighting spans of the input `lines` combined with the prepared
+    /// [`Text::styles`], or `None` if there is no highlighter
+    pub(super) fn highlighted_styles(&self, lines: Range<usize>) -> Option<Vec<StyleSpan>> {
+        let highlighting = self.highlighting.as_ref()?;
+        let mut user_styles = self
+            .styles
+            .iter()
+            .filter(|s| lines.contains(&s.line))
+            .peekable();
+        let mut styles = Vec::new();
+
+        let spans = highlighting.spans.iter().enumerate();
+        for (line, spans) in spans.skip(lines.start).take(lines.len()) {
+            // Line indices may be outdated after edits
+            styles.extend(spans.iter().map(|span| StyleSpan {
+                line,
+                ..span.clone()
+            }));
+
+            // User styles are applied last to override the highlighting
+            while let Some(style) = user_styles.next_if(|s| s.line <= line) {
+                styles.push(style.clone());
+            }
+        }
+        styles.extend(user_styles.cloned());
+
+        Some(self.flatten_styles(styles))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// State of the [`RustHighlighter`] at the end of a line
+enum RustState {
+    Code,
+    String,
+    /// Raw string with the number of `#`
+    RawString(u32),
+    /// Block comment with its nesting depth
+    BlockComment(u32),
+}
+
+impl RustState {
+    fn from_u32(state: u32) -> Self {
+        match state & 0b11 {
+            1 => Self::String,
+            2 => Self::RawString(state >> 2),
+            3 => Self::BlockComment(state >> 2),
+            _ => Self::Code,
+        }
+    }
+
+    fn into_u32(self) -> u32 {
+        match self {
+            Self::Code => 0,
+            Self::String => 1,
+            Self::RawString(hashes) => hashes << 2 | 2,
+            Self::BlockComment(depth) => depth << 2 | 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Built-in [`Highlighter`] for Rust source code. Highlights keywords, types, macros, strings,
+/// numbers and comments, including multi-line strings and nested block comments.
+pub struct RustHighlighter {
+    pub keyword: Color,
+    pub type_name: Color,
+    pub macro_name: Color,
+    pub string: Color,
+    pub number: Color,
+    pub comment: Color,
+}
+
+impl Default for RustHighlighter {
+    fn default() -> Self {
+        Self {
+            keyword: Color::Magenta,
+            type_name: Color::Yellow,
+            macro_name: Color::Blue,
+            string: Color::Green,
+            number: Color::Cyan,
+            comment: Color::DarkGrey,
+        }
+    }
+}
+
+impl RustHighlighter {
+    const KEYWORDS: [&str; 39] = [
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while", "yield",
+    ];
+
+    const PRIMITIVES: [&str; 17] = [
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ];
+
+    /// Returns the end of a block comment at `depth` starting at `i`, and the depth at the end
+    fn scan_block_comment(g: &[&str], mut i: usize, mut depth: u32) -> (usize, u32) {
+        while i < g.len() && depth > 0 {
+            match (char_at(g, i), char_at(g, i + 1)) {
+                ('*', '/') => (depth, i) = (depth - 1, i + 2),
+                ('/', '*') => (depth, i) = (depth + 1, i + 2),
+                _ => i += 1,
+            }
+        }
+        (i.min(g.len()), depth)
+    }
+
+    /// Returns the end of a string starting at `i` after the opening quote, and whether it is
+    /// closed on this line
+    fn scan_string(g: &[&str], mut i: usize) -> (usize, bool) {
+        while i < g.len() {
+            match char_at(g, i) {
+                '\\' => i += 2,
+                '"' => return (i + 1, true),
+                _ => i += 1,
+            }
+        }
+        (g.len(), false)
+    }
+
+    /// Returns the end of a raw string with `hashes` starting at `i` after the opening quote, and
+    /// whether it is closed on this line
+    fn scan_raw_string(g: &[&str], mut i: usize, hashes: u32) -> (usize, bool) {
+        let hashes = hashes as usize;
+        while i < g.len() {
+            if char_at(g, i) == '"' && (1..=hashes).all(|h| char_at(g, i + h) == '#') {
+                return (i + 1 + hashes, true);
+            }
+            i += 1;
+        }
+        (g.len(), false)
+    }
+
+    /// Returns the end of an identifier starting at `i`
+    fn scan_ident(g: &[&str], mut i: usize) -> usize {
+        while i < g.len() && is_ident_char(char_at(g, i)) {
+            i += 1;
+        }
+        i
+    }
+}
+
+impl Highlighter for RustHighlighter {
+    fn highlight_line(
+        &mut self,
+        line: &BufferLine,
+        line_index: usize,
+        state: &mut u32,
+    ) -> Vec<StyleSpan> {
+        let g = line.graphemes().collect::<Vec<_>>();
+        let mut spans = Vec::new();
+        let mut push = |color: Color, start: usize, end: usize| {
+            if end > start {
+                let code = Code::Foreground(color);
+                spans.push(StyleSpan::new(code, line_index, start, end - start));
+            }
+        };
+
+        // Finish the construct left open by the previous line
+        let start_state = RustState::from_u32(*state);
+        let (mut i, mut rust_state) = match start_state {
+            RustState::Code => (0, RustState::Code),
+            RustState::String => match Self::scan_string(&g, 0) {
+                (end, true) => (end, RustState::Code),
+                (end, false) => (end, RustState::String),
+            },
+            RustState::RawString(hashes) => match Self::scan_raw_string(&g, 0, hashes) {
+                (end, true) => (end, RustState::Code),
+                (end, false) => (end, RustState::RawString(hashes)),
+            },
+            RustState::BlockComment(depth) => match Self::scan_block_comment(&g, 0, depth) {
+                (end, 0) => (end, RustState::Code),
+                (end, depth) => (end, RustState::BlockComment(depth)),
+            },
+        };
+        let color = match start_state {
+            RustState::BlockComment(_) => self.comment,
+            _ => self.string,
+        };
+        push(color, 0, i);
+
+        while i < g.len() && rust_state == RustState::Code {
+            let start = i;
+            let char = char_at(&g, i);
+
+            match char {
+                '/' if char_at(&g, i + 1) == '/' => {
+                    push(self.comment, i, g.len());
+                    i = g.len();
+                }
+                '/' if char_at(&g, i + 1) == '*' => {
+                    let (end, depth) = Self::scan_block_comment(&g, i + 2, 1);
+                    push(self.comment, i, end);
+                    if depth > 0 {
+                        rust_state = RustState::BlockComment(depth);
+                    }
+                    i = end;
+                }
+                '"' => {
+                    let (end, closed) = Self::scan_string(&g, i + 1);
+                    push(self.string, i, end);
+                    if !closed {
+                        rust_state = RustState::String;
+                    }
+                    i = end;
+                }
+                '\'' => {
+                    if char_at(&g, i + 1) == '\\' {
+                        // Escaped char literal
+                        let end = (i + 3..g.len())
+                            .find(|&j| char_at(&g, j) == '\'')
+                            .map_or(g.len(), |j| j + 1);
+                        push(self.string, i, end);
+                        i = end;
+                    } else if i + 1 < g.len() && char_at(&g, i + 2) == '\'' {
+                        push(self.string, i, i + 3);
+                        i += 3;
+                    } else if is_ident_start(char_at(&g, i + 1)) {
+                        // Lifetime or label
+                        let end = Self::scan_ident(&g, i + 1);
+                        push(self.keyword, i, end);
+                        i = end;
+                    } else {
+                        i += 1;
+                    }
+                }
+                _ if char.is_ascii_digit() => {
+                    i += 1;
+                    while i < g.len() {
+                        let char = char_at(&g, i);
+                        let fraction = char == '.' && char_at(&g, i + 1).is_ascii_digit();
+                        if !(is_ident_char(char) || fraction) {
+                            break;
+                        }
+                        i += 1;
+                    }
+                    push(self.number, start, i);
+                }
+                _ if is_ident_start(char) => {
+                    let end = Self::scan_ident(&g, i);
+                    let word = g[i..end].concat();
+                    let next = char_at(&g, end);
+
+                    // String prefixes, like `b"bytes"` or `r#"raw"#`
+                    let raw = matches!(word.as_str(), "r" | "br" | "cr");
+                    let hashes = (end..g.len())
+                        .take_while(|&j| char_at(&g, j) == '#')
+                        .count();
+
+                    if raw && char_at(&g, end + hashes) == '"' {
+                        let hashes = hashes as u32;
+                        let (end, closed) =
+                            Self::scan_raw_string(&g, end + 1 + hashes as usize, hashes);
+                        push(self.string, i, end);
+                        if !closed {
+                            rust_state = RustState::RawString(hashes);
+                        }
+                        i = end;
+                    } else if matches!(word.as_str(), "b" | "c") && next == '"' {
+                        let (end, closed) = Self::scan_string(&g, end + 1);
+                        push(self.string, i, end);
+                        if !closed {
+                            rust_state = RustState::String;
+                        }
+                        i = end;
+                    } else if Self::KEYWORDS.contains(&word.as_str()) {
+                        push(self.keyword, i, end);
+                        i = end;
+                    } else if next == '!' && char_at(&g, end + 1) != '=' {
+                        push(self.macro_name, i, end + 1);
+                        i = end + 1;
+                    } else {
+                        let is_type =
+                            char.is_uppercase() || Self::PRIMITIVES.contains(&word.as_str());
+                        if is_type {
+                            push(self.type_name, i, end);
+                        }
+                        i = end;
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+
+        *state = rust_state.into_u32();
+        spans
+    }
+}
+
+/// Returns the first char of the grapheme at `i`, or `\0` if out of bounds
+fn char_at(graphemes: &[&str], i: usize) -> char {
+    graphemes
+        .get(i)
+        .and_then(|g| g.chars().next())
+        .unwrap_or('\0')
+}
+
+#[inline]
+fn is_ident_start(char: char) -> bool {
+    char.is_alphabetic() || char == '_'
+}
+
+#[inline]
+fn is_ident_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
+#[cfg(test)]
+mod highlight_tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<BufferLine> {
+        lines
+            .iter()
+            .map(|l| BufferLine::new(l.to_string()))
+            .collect()
+    }
+
+    /// Returns `(color, line, start, length)` of the spans
+    fn spans(spans: &[StyleSpan]) -> Vec<(Color, usize, usize, usize)> {
+        spans
+            .iter()
+            .map(|s| match s.code {
+                Code::Foreground(color) => (color, s.line, s.character, s.length),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rust_tokens() {
+        let h = RustHighlighter::default();
+        let input = lines(&[r#"pub fn a<'b>(x: &'b str) -> u8 { println!("{x}\""); 1_0 } // c"#]);
+        let spans = spans(&RustHighlighter::default().highlight(&input));
+
+        assert_eq!(
+            spans,
+            [
+                (h.keyword, 0, 0, 3),
+                (h.keyword, 0, 4, 2),
+                (h.keyword, 0, 9, 2),
+                (h.keyword, 0, 17, 2),
+                (h.type_name, 0, 20, 3),
+                (h.type_name, 0, 28, 2),
+                (h.macro_name, 0, 33, 8),
+                (h.string, 0, 42, 7),
+                (h.number, 0, 52, 3),
+                (h.comment, 0, 58, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_multiline_constructs() {
+        let h = RustHighlighter::default();
+        let input = lines(&["/* a /* b */", "c */ x", r##"r#"raw"##, r##"x"# 'c'"##]);
+        let spans = spans(&RustHighlighter::default().highlight(&input));
+
+        assert_eq!(
+            spans,
+            [
+                (h.comment, 0, 0, 12),
+                (h.comment, 1, 0, 4),
+                (h.string, 2, 0, 6),
+                (h.string, 3, 0, 3),
+                (h.string, 3, 4, 3),
+            ]
+        );
+    }
+
+    /// Counts the highlighted lines
+    struct Counting(RustHighlighter, Rc<Cell<usize>>);
+
+    impl Highlighter for Counting {
+        fn highlight_line(
+            &mut self,
+            line: &BufferLine,
+            line_index: usize,
+            state: &mut u32,
+        ) -> Vec<StyleSpan> {
+            self.1.set(self.1.get() + 1);
+            self.0.highlight_line(line, line_index, state)
+        }
+    }
+
+    #[test]
+    fn incremental_edits() {
+        let count = Rc::new(Cell::new(0));
+        let mut text = Text::plain("let a = 1;\nlet b = 2;\nlet c = 3;\nlet d = 4;");
+        text.set_highlighter(Counting(RustHighlighter::default(), count.clone()));
+        assert_eq!(count.replace(0), 4);
+
+        // Same state after the edited line, the rest is not highlighted again
+        text.replace_lines(1..2, lines(&["let b = \"2\";"]));
+        assert_eq!(count.replace(0), 1);
+
+        // Opening a block comment highlights the following lines
+        text.replace_lines(1..2, lines(&["/* b"]));
+        assert_eq!(count.replace(0), 3);
+        let highlighting = text.highlighting.as_ref().unwrap();
+        assert_eq!(highlighting.states, [0, 7, 7, 7]);
+
+        // Inserted lines shift the following lines
+        text.replace_lines(0..0, lines(&["*/"]));
+        let highlighting = text.highlighting.as_ref().unwrap();
+        assert_eq!(highlighting.states, [0, 0, 7, 7, 7]);
+        assert_eq!(
+            text.highlighted_styles(0..text.input.len()).unwrap()[0].line,
+            1
+        );
+    }
+
+    #[test]
+    fn user_styles_override_highlighting() {
+        let mut text = Text::plain("let a");
+        text.styles = vec![StyleSpan::new(Code::Foreground(Color::Red), 0, 0, 1)];
+        text.set_highlighter(RustHighlighter::default());
+
+        let styles = spans(&text.highlighted_styles(0..text.input.len()).unwrap());
+        let keyword = RustHighlighter::default().keyword;
+        assert_eq!(styles, [(Color::Red, 0, 0, 1), (keyword, 0, 1, 2)]);
+    }
+}
@@ -0,0 +1,394 @@
+use std::fmt::Display;
+
+use crossterm::style::{Attribute, Color};
+
+use crate::Code;
+
+use super::{ByteSpan, Text, into_style_spans};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error returned by [`Text::markup`], positions are byte indices in the markup
+pub enum MarkupError {
+    /// The tag starting at the position is missing its closing `]`
+    UnclosedTag(usize),
+    /// The tag at the position has no styles
+    EmptyTag(usize),
+    /// The tag at the position contains an unknown style
+    UnknownStyle(usize, String),
+    /// The closing tag at the position has no open tag to close
+    UnexpectedClose(usize),
+    /// The closing tag at the position does not match the innermost open tag
+    MismatchedClose {
+        position: usize,
+        expected: String,
+        found: String,
+    },
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnclosedTag(i) => write!(f, "unclosed tag at {i}, expected `]`"),
+            Self::EmptyTag(i) => write!(f, "empty tag at {i}"),
+            Self::UnknownStyle(i, style) => write!(f, "unknown style `{style}` in tag at {i}"),
+            Self::UnexpectedClose(i) => write!(f, "closing tag at {i} has no open tag"),
+            Self::MismatchedClose {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "closing tag `[/{found}]` at {position} does not match `[{expected}]`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+/// Open markup tag
+struct OpenTag {
+    /// Normalized tag content
+    tag: String,
+    /// Index of the tag's span
+    span: usize,
+}
+
+impl Text {
+    /// Creates a text object from `markup`, a string with inline style tags.
+    ///
+    /// A tag like `[bold red on #202020]` applies its styles until the matching `[/]` or
+    /// `[/bold red on #202020]` closing tag, tags can be nested and unclosed tags end with the
+    /// text. `\[` and `\\` escape the bracket and the backslash.
+    ///
+    /// - Attributes: `bold` (`b`), `dim`, `italic` (`i`), `underline` (`u`), `double_underline`,
+    ///   `undercurl`, `underdotted`, `underdashed`, `blink`, `rapid_blink`, `reverse`, `hidden`,
+    ///   `strike` (`s`), `overline`
+    /// - Colors: named colors like `red` or `dark_grey`, `#rgb`, `#rrggbb`, `rgb(r, g, b)` and
+    ///   `ansi(n)`. Colors are applied to the foreground, or to the background after `on`
+    ///
+    /// ```ignore
+    /// let text = Text::markup("[bold red]Error:[/] file [u]not found[/]")?;
+    /// ```
+    pub fn markup(markup: &str) -> Result<Self, MarkupError> {
+        let mut plain = String::new();
+        let mut spans = Vec::<ByteSpan>::new();
+        let mut open = Vec::<OpenTag>::new();
+        let mut chars = markup.char_indices().peekable();
+
+        while let Some((i, char)) = chars.next() {
+            match char {
+                '\\' => match chars.peek() {
+                    Some(&(_, escaped @ ('[' | '\\'))) => {
+                        plain.push(escaped);
+                        chars.next();
+                    }
+                    _ => plain.push('\\'),
+                },
+                '[' => {
+                    let content_start = i + 1;
+                    let end = markup[content_start..]
+                        .find(']')
+                        .map(|end| content_start + end)
+                        .ok_or(MarkupError::UnclosedTag(i))?;
+
+                    // Skip the tag content
+                    while chars.next_if(|&(j, _)| j <= end).is_some() {}
+
+                    let content = &markup[content_start..end];
+                    if let Some(close) = content.strip_prefix('/') {
+                        let close = tokenize(close).join(" ");
+                        let tag = open.pop().ok_or(MarkupError::UnexpectedClose(i))?;
+
+                        if !close.is_empty() && close != tag.tag {
+                            return Err(MarkupError::MismatchedClose {
+                                position: i,
+                                expected: tag.tag,
+                                found: close,
+                            });
+                        }
+
+                        spans[tag.span].end = plain.len();
+                    } else {
+                        let tokens = tokenize(content);
+                        let codes = parse_codes(&tokens, i)?;
+
+                        open.push(OpenTag {
+                            tag: tokens.join(" "),
+                            span: spans.len(),
+                        });
+                        spans.push(ByteSpan {
+                            codes,
+                            start: plain.len(),
+                            end: usize::MAX,
+                        });
+                    }
+                }
+                _ => plain.push(char),
+            }
+        }
+
+        let mut text = Text::plain(&plain);
+        let styles = into_style_spans(&plain, &text.input, spans);
+        text.add_styles(styles);
+
+        Ok(text)
+    }
+}
+
+/// Splits tag content at whitespace outside of parentheses
+fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut depth = 0;
+
+    for char in content.chars() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if char.is_whitespace() => {
+                if depth <= 0 && !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        token.push(char);
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Parses the style codes of a tag at `position`
+fn parse_codes(tokens: &[String], position: usize) -> Result<Vec<Code>, MarkupError> {
+    if tokens.is_empty() {
+        return Err(MarkupError::EmptyTag(position));
+    }
+
+    let mut codes = Vec::new();
+    let mut background = false;
+
+    for token in tokens {
+        let unknown = || MarkupError::UnknownStyle(position, token.clone());
+
+        if token == "on" {
+            background = true;
+            continue;
+        }
+
+        if background {
+            let color = parse_color(token).ok_or_else(unknown)?;
+            codes.push(Code::Background(color));
+            background = false;
+        } else if let Some(attr) = parse_attribute(token) {
+            codes.push(Code::Attribute(attr));
+        } else {
+            let color = parse_color(token).ok_or_else(unknown)?;
+            codes.push(Code::Foreground(color));
+        }
+    }
+
+    // `on` without a color
+    if background {
+        return Err(MarkupError::UnknownStyle(position, "on".into()));
+    }
+
+    Ok(codes)
+}
+
+/// Parses an attribute name
+fn parse_attribute(name: &str) -> Option<Attribute> {
+    let attr = match name {
+        "bold" | "b" => Attribute::Bold,
+        "dim" => Attribute::Dim,
+        "italic" | "i" => Attribute::Italic,
+        "underline" | "u" => Attribute::Underlined,
+        "double_underline" => Attribute::DoubleUnderlined,
+        "undercurl" => Attribute::Undercurled,
+        "underdotted" => Attribute::Underdotted,
+        "underdashed" => Attribute::Underdashed,
+        "blink" => Attribute::SlowBlink,
+        "rapid_blink" => Attribute::RapidBlink,
+        "reverse" => Attribute::Reverse,
+        "hidden" => Attribute::Hidden,
+        "strike" | "s" => Attribute::CrossedOut,
+        "overline" => Attribute::OverLined,
+        _ => return None,
+    };
+
+    Some(attr)
+}
+
+/// Parses a named, hex, `rgb(r, g, b)` or `ansi(n)` color
+fn parse_color(color: &str) -> Option<Color> {
+    if let Some(hex) = color.strip_prefix('#') {
+        let channel = |i: usize, len: usize| {
+            let value = u8::from_str_radix(hex.get(i * len..(i + 1) * len)?, 16).ok()?;
+            // Expand `#rgb` shorthand channels
+            Some(if len == 1 { value * 17 } else { value })
+        };
+
+        let len = match hex.len() {
+            3 => 1,
+            6 => 2,
+            _ => return None,
+        };
+        return Some(Color::Rgb {
+            r: channel(0, len)?,
+            g: channel(1, len)?,
+            b: channel(2, len)?,
+        });
+    }
+
+    let arguments = |prefix: &str| {
+        color
+            .strip_prefix(prefix)?
+            .strip_suffix(')')
+            .map(|args| args.split(',').map(|arg| arg.trim().parse::<u8>().ok()))
+    };
+
+    if let Some(mut args) = arguments("rgb(") {
+        let (r, g, b) = (args.next()??, args.next()??, args.next()??);
+        return args.next().is_none().then_some(Color::Rgb { r, g, b });
+    }
+
+    if let Some(mut args) = arguments("ansi(") {
+        let value = args.next()??;
+        return args.next().is_none().then_some(Color::AnsiValue(value));
+    }
+
+    let color = match color {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+#[cfg(test)]
+mod markup_tests {
+    use super::*;
+
+    fn spans(text: &Text) -> Vec<(Code, usize, usize, usize)> {
+        text.styles
+            .iter()
+            .map(|s| (s.code, s.line, s.character, s.length))
+            .collect()
+    }
+
+    fn content(text: &Text) -> Vec<&str> {
+        text.input.iter().map(|l| l.content()).collect()
+    }
+
+    #[test]
+    fn plain_text_and_spans() {
+        let text = Text::markup("[bold red]Error:[/] file [u]not found[/]").unwrap();
+        assert_eq!(content(&text), ["Error: file not found"]);
+
+        let mut styles = spans(&text);
+        styles.sort_by_key(|s| s.2);
+        assert_eq!(
+            styles,
+            [
+                (Code::Foreground(Color::Red), 0, 0, 6),
+                (Code::Attribute(Attribute::Bold), 0, 0, 6),
+                (Code::Attribute(Attribute::Underlined), 0, 12, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_tags_override_outer() {
+        let text = Text::markup("[red]a[blue]b[/blue]c[/]").unwrap();
+        let mut styles = spans(&text);
+        styles.sort_by_key(|s| s.2);
+        assert_eq!(
+            styles,
+            [
+                (Code::Foreground(Color::Red), 0, 0, 1),
+                (Code::Foreground(Color::Blue), 0, 1, 1),
+                (Code::Foreground(Color::Red), 0, 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_across_lines() {
+        let text = Text::markup("[on #ff8000]ab\ncd[/]").unwrap();
+        let color = Code::Background(Color::Rgb {
+            r: 255,
+            g: 128,
+            b: 0,
+        });
+        assert_eq!(spans(&text), [(color, 0, 0, 2), (color, 1, 0, 2)]);
+    }
+
+    #[test]
+    fn colors() {
+        let rgb = |r, g, b| Some(Color::Rgb { r, g, b });
+        assert_eq!(parse_color("#fff"), rgb(255, 255, 255));
+        assert_eq!(parse_color("#102030"), rgb(16, 32, 48));
+        assert_eq!(parse_color("rgb(1, 2, 3)"), rgb(1, 2, 3));
+        assert_eq!(parse_color("ansi(42)"), Some(Color::AnsiValue(42)));
+        assert_eq!(parse_color("dark_grey"), Some(Color::DarkGrey));
+        assert_eq!(parse_color("#12"), None);
+        assert_eq!(parse_color("rgb(1, 2)"), None);
+        assert_eq!(tokenize("red on rgb(1, 2, 3)"), ["red", "on", "rgb(1,2,3)"]);
+    }
+
+    #[test]
+    fn escapes() {
+        let text = Text::markup(r"\[not a tag] C:\dir \\").unwrap();
+        assert_eq!(content(&text), [r"[not a tag] C:\dir \"]);
+        assert!(text.styles.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Text::markup("a [bold").unwrap_err(),
+            MarkupError::UnclosedTag(2)
+        );
+        assert_eq!(Text::markup("[ ]").unwrap_err(), MarkupError::EmptyTag(0));
+        assert_eq!(
+            Text::markup("[bold purple]").unwrap_err(),
+            MarkupError::UnknownStyle(0, "purple".into())
+        );
+        assert_eq!(
+            Text::markup("[red on]").unwrap_err(),
+            MarkupError::UnknownStyle(0, "on".into())
+        );
+        assert_eq!(
+            Text::markup("a[/]").unwrap_err(),
+            MarkupError::UnexpectedClose(1)
+        );
+        assert_eq!(
+            Text::markup("[b]a[/i]").unwrap_err(),
+            MarkupError::MismatchedClose {
+                position: 4,
+                expected: "b".into(),
+                found: "i".into()
+            }
+        );
+    }
+}
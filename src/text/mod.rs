@@ -1,9 +1,14 @@
+mod ansi;
 mod attrs;
 mod buffer_line;
+mod highlight;
+mod markup;
 mod visual;
 
 pub use attrs::*;
 pub use buffer_line::*;
+pub use highlight::*;
+pub use markup::*;
 pub use visual::*;
 
 use std::{ops::Range, path::Path};
@@ -13,9 +18,9 @@ use crate::{Code, code::CodeUnit};
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 /// Text wrapping options
 pub enum TextWrap {
-    /// No wrapping
+    /// No wrapping, overflowing lines are clipped or scrolled horizontally
     None,
-    /// Break at whitespace
+    /// Break at line break opportunities, like whitespace, long words are broken at graphemes
     Word,
     /// Break at character
     #[default]
@@ -33,10 +38,14 @@ pub struct Text {
     pub wrap: TextWrap,
     /// Cursor position in the text, used for rendering
     pub cursor: Option<(u16, u16)>,
+    /// Syntax highlighting of the input, see [`Text::set_highlighter`]
+    highlighting: Option<Highlighting>,
+    /// Wrapping style and width of the visual lines, `None` if they are not wrapped
+    wrapped: Option<(TextWrap, u16)>,
 }
 
 impl Text {
-    fn new_from(input: Vec<BufferLine>) -> Self {
+    pub(crate) fn new_from(input: Vec<BufferLine>) -> Self {
         // let original_size = (
         //     input.iter().map(|l| l.chars().count()).max().unwrap_or(0),
         //     input.len(),
@@ -48,14 +57,21 @@ impl Text {
             styles: Vec::new(),
             wrap: TextWrap::default(),
             cursor: None,
+            highlighting: None,
+            wrapped: None,
         }
     }
 
-    /// Wraps the visual text to the specified width. Must be called after `prepare_text`, calling
-    /// this function multiple times will not have the desired effect.
+    /// Wraps the visual text to the specified width, based on the [`wrap`](TextWrap) style. Must
+    /// be called after `prepare_text`. Lines already wrapped to `width` with the same style are
+    /// kept as they are.
     ///
     /// Returns the number of lines that were added due to wrapping.
     pub fn wrap_text(&mut self, width: u16) -> usize {
+        if self.wrapped == Some((self.wrap, width)) {
+            return 0;
+        }
+
         let mut unwrapped_lines = Vec::new();
         let mut current_line = None;
 
@@ -88,12 +104,18 @@ impl Text {
             unwrapped_lines.push(current);
         }
 
-        let unwrapped_len = self.visual.len();
+        let unwrapped_len = unwrapped_lines.len();
 
+        let wrap = self.wrap;
         self.visual = unwrapped_lines
             .into_iter()
-            .flat_map(|line| line.into_wrapped(width))
+            .flat_map(|line| match wrap {
+                TextWrap::None => vec![line],
+                TextWrap::Word => line.into_word_wrapped(width),
+                TextWrap::All => line.into_wrapped(width),
+            })
             .collect();
+        self.wrapped = Some((wrap, width));
 
         self.visual.len() - unwrapped_len
     }
@@ -102,20 +124,32 @@ impl Text {
     /// Height will be clamped to the terminal size.
     pub fn prepare_text(&mut self, height: u16) {
         let skip = 0; // TODO: skip lines based on cursor position or other criteria
-        let mut visual_lines = Vec::new();
         let terminal_height = crossterm::terminal::size().map_or(height, |(_, h)| h);
         let height = height.min(terminal_height);
 
+        self.prepare_lines(skip..skip + height as usize);
+    }
+
+    /// Prepares the visual text for the input lines in `lines` and applies styles. Unlike
+    /// [`Text::prepare_text`], the range is not clamped to the terminal size.
+    pub fn prepare_lines(&mut self, lines: Range<usize>) {
+        let mut visual_lines = Vec::new();
+
         // Prepare styles
         self.prepare_styles();
-        let mut styles = self.styles.iter().peekable();
+        let highlighted = self.highlighted_styles(lines.clone());
+        let mut styles = highlighted
+            .as_ref()
+            .unwrap_or(&self.styles)
+            .iter()
+            .peekable();
 
         for (line_index, line) in self
             .input
             .iter()
             .enumerate()
-            .skip(skip)
-            .take(height as usize)
+            .skip(lines.start)
+            .take(lines.len())
         {
             let grapheme_count = line.count();
             let mut visual_line = VisualLine::from_buffer_line(line, line_index);
@@ -148,9 +182,35 @@ impl Text {
         }
 
         self.visual = visual_lines;
+        self.wrapped = None;
         self.sanitize();
     }
 
+    /// Prepares the visual text after `removed` input lines starting at `start` have been
+    /// replaced by `inserted` lines. Only the inserted lines are prepared and wrapped like the
+    /// rest, the visual lines of the other input lines are kept and their line indices shifted.
+    /// The text has to be prepared before the edit.
+    pub fn prepare_edit(&mut self, start: usize, removed: usize, inserted: usize) {
+        let from = self.visual.partition_point(|l| l.line_index < start);
+        let to = self
+            .visual
+            .partition_point(|l| l.line_index < start + removed);
+        let wrapped = self.wrapped;
+        let mut visual = std::mem::take(&mut self.visual);
+
+        self.prepare_lines(start..start + inserted);
+        if let Some((_, width)) = wrapped {
+            self.wrap_text(width);
+        }
+
+        for line in &mut visual[to..] {
+            line.line_index = line.line_index + inserted - removed;
+        }
+        visual.splice(from..to, self.visual.drain(..));
+        self.visual = visual;
+        self.wrapped = wrapped;
+    }
+
     /// Creates a text object from a string
     pub fn plain(input: &str) -> Self {
         let mut lines = input
@@ -212,12 +272,13 @@ impl Text {
         }
     }
 
-    /// Flattens styles into a contiguous non-overlapping array
+    /// Flattens styles into a contiguous non-overlapping array, later styles override earlier
+    /// ones on the same line
     /// # Note
-    /// Must be called after sorting styles
-    fn flatten_styles(&mut self) {
-        if self.styles.is_empty() {
-            return;
+    /// Styles must be sorted by line
+    fn flatten_styles(&self, styles: Vec<StyleSpan>) -> Vec<StyleSpan> {
+        if styles.is_empty() {
+            return styles;
         }
 
         /// Set a code range
@@ -231,6 +292,8 @@ impl Text {
                     Code::Attribute(attr) => line[i].apply_attr(attr),
                     Code::Background(bg) => line[i].set_bg(bg),
                     Code::Foreground(fg) => line[i].set_fg(fg),
+                    Code::UnderlineColor(color) => line[i].set_underline_color(color),
+                    Code::Hyperlink(link) => line[i].set_link(link),
                 }
             }
         }
@@ -247,6 +310,14 @@ impl Text {
             let mut bg = None;
             let mut bg_i = 0;
 
+            // Active underline color and start index
+            let mut underline = None;
+            let mut underline_i = 0;
+
+            // Active hyperlink and start index
+            let mut link = None;
+            let mut link_i = 0;
+
             // Currently active attributes and their start indices
             let mut attrs = Attrs::default().extract();
             let mut attrs_i = (0..attrs.len()).collect::<Vec<_>>();
@@ -257,6 +328,8 @@ impl Text {
 
                 let unit_fg = unit.and_then(|u| u.fg());
                 let unit_bg = unit.and_then(|u| u.bg());
+                let unit_underline = unit.and_then(|u| u.underline_color());
+                let unit_link = unit.and_then(|u| u.link());
                 let unit_attrs = unit
                     .map(|u| u.attrs().extract())
                     .unwrap_or_else(|| Attrs::default().extract());
@@ -279,6 +352,26 @@ impl Text {
                     bg_i = i;
                 }
 
+                if unit_underline != underline {
+                    if let Some(color) = underline {
+                        let code = Code::UnderlineColor(color);
+                        styles.push(StyleSpan::new(code, li, underline_i, i - underline_i));
+                    }
+
+                    underline = unit_underline;
+                    underline_i = i;
+                }
+
+                if unit_link != link {
+                    if let Some(link) = link {
+                        let code = Code::Hyperlink(Some(link));
+                        styles.push(StyleSpan::new(code, li, link_i, i - link_i));
+                    }
+
+                    link = unit_link;
+                    link_i = i;
+                }
+
                 for ai in 0..attrs.len() {
                     let attr = attrs[ai];
                     let unit_attr = unit_attrs[ai];
@@ -311,15 +404,15 @@ impl Text {
             .unwrap_or_default();
 
         let mut line = Vec::<CodeUnit>::new();
-        let mut styles = vec![];
+        let mut flattened = vec![];
         let mut last_line = 0;
-        for style in self.styles.drain(..) {
+        for style in styles {
             if style.line < last_line {
                 panic!("unsorted styles");
             }
 
             if style.line > last_line {
-                styles.extend(combine(&line, last_line));
+                flattened.extend(combine(&line, last_line));
                 last_line = style.line;
                 line = Vec::new();
             }
@@ -329,10 +422,10 @@ impl Text {
         }
 
         if !line.is_empty() {
-            styles.extend(combine(&line, last_line));
+            flattened.extend(combine(&line, last_line));
         }
 
-        self.styles = styles;
+        flattened
     }
 
     /// Sort styles by index
@@ -350,7 +443,8 @@ impl Text {
     /// Sort and flatten styles
     pub fn prepare_styles(&mut self) {
         self.sort_styles();
-        self.flatten_styles();
+        let styles = std::mem::take(&mut self.styles);
+        self.styles = self.flatten_styles(styles);
     }
 
     /// Adds new styles to the existing one, re-prepares text
@@ -374,6 +468,57 @@ impl Text {
     }
 }
 
+/// Styled byte range of a plain string, used to build [`StyleSpan`]s when parsing styled input
+struct ByteSpan {
+    codes: Vec<Code>,
+    start: usize,
+    end: usize,
+}
+
+/// Converts byte spans of the `plain` text into per line grapheme [`StyleSpan`]s, `lines` are the
+/// input lines of `plain`. Graphemes partially covered by a span are included.
+fn into_style_spans(plain: &str, lines: &[BufferLine], spans: Vec<ByteSpan>) -> Vec<StyleSpan> {
+    // Byte index of each line start
+    let line_starts = std::iter::once(0)
+        .chain(plain.match_indices('\n').map(|(i, _)| i + 1))
+        .take(lines.len())
+        .collect::<Vec<_>>();
+
+    let mut styles = Vec::new();
+    for span in spans {
+        let end = span.end.min(plain.len());
+        let first_line = line_starts.partition_point(|&start| start <= span.start) - 1;
+
+        for (line_index, &line_start) in line_starts.iter().enumerate().skip(first_line) {
+            if line_start > end {
+                break;
+            }
+
+            let line = &lines[line_index];
+            let line_end = line_start + line.content().len();
+            let (start, end) = (
+                span.start.saturating_sub(line_start),
+                end.min(line_end).saturating_sub(line_start),
+            );
+
+            let data = line.grapheme_data();
+            let start = data
+                .iter()
+                .take_while(|(i, len, _)| i + len <= start)
+                .count();
+            let end = data.iter().take_while(|(i, ..)| *i < end).count();
+
+            if end > start {
+                for code in &span.codes {
+                    styles.push(StyleSpan::new(*code, line_index, start, end - start));
+                }
+            }
+        }
+    }
+
+    styles
+}
+
 impl Into<Text> for &str {
     fn into(self) -> Text {
         Text::plain(self)
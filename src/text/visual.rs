@@ -154,13 +154,14 @@ impl VisualLine {
 
         let mut content = self.content.into_iter().peekable();
 
-        for _ in 0..parts {
+        while content.peek().is_some() {
             let mut line_content = Vec::new();
             let mut line_width = 0;
+            let line_offset = offset;
 
             while let Some(unit) = content.peek() {
                 let unit_width = unit.width();
-                if line_width + unit_width > max_width as usize {
+                if line_width + unit_width > max_width as usize && line_width > 0 {
                     // If adding this unit exceeds the max width, break
                     break;
                 }
@@ -179,13 +180,146 @@ impl VisualLine {
             lines.push(Self {
                 content: line_content,
                 line_index,
-                offset,
+                offset: line_offset,
             });
         }
 
         lines
     }
 
+    /// Returns a `vec` of visual lines, which are wrapped parts of this line broken at the line
+    /// break opportunities of the Unicode line breaking algorithm (UAX #14). Words longer than
+    /// `max_width` are broken at graphemes. Whitespace at the end of a line is kept, even if it overflows.
+    pub fn into_word_wrapped(self, max_width: u16) -> Vec<Self> {
+        if max_width == 0 || self.width() <= max_width as usize {
+            return self.into_wrapped(max_width);
+        }
+
+        let max_width = max_width as usize;
+        let line_index = self.line_index;
+
+        // Unbreakable segments, each ends with its trailing whitespace
+        let mut segments = vec![Vec::new()];
+        let mut breaks = self.break_opportunities().into_iter().enumerate();
+        for unit in &self.content {
+            if unit.is_grapheme()
+                && let Some((i, can_break)) = breaks.next()
+                && i > 0
+                && can_break
+            {
+                segments.push(Vec::new());
+            }
+
+            segments
+                .last_mut()
+                .expect("Segments are not empty")
+                .push(unit.clone());
+        }
+
+        let mut lines = Vec::new();
+        let mut line = Self {
+            content: Vec::new(),
+            line_index,
+            offset: self.offset,
+        };
+        let mut line_width = 0;
+        let mut offset = self.offset;
+
+        for segment in segments {
+            let width = segment.iter().map(|u| u.width()).sum::<usize>();
+            let trailing_whitespace = segment
+                .iter()
+                .rev()
+                .filter(|u| u.is_grapheme())
+                .take_while(|u| matches!(u, StyledUnit::Grapheme(g) if Self::is_whitespace(g)))
+                .map(|u| u.width())
+                .sum::<usize>();
+            let graphemes = segment.iter().filter(|u| u.is_grapheme()).count();
+
+            // Trailing whitespace can overflow the line
+            if line_width + width - trailing_whitespace > max_width && line_width > 0 {
+                lines.push(std::mem::replace(
+                    &mut line,
+                    Self {
+                        content: Vec::new(),
+                        line_index,
+                        offset,
+                    },
+                ));
+                line_width = 0;
+            }
+
+            if width - trailing_whitespace > max_width {
+                // Over-long word, break it at graphemes and continue after its last part, the
+                // trailing whitespace is kept on it
+                let mut content = segment;
+                let word_end = content
+                    .iter()
+                    .rposition(|u| matches!(u, StyledUnit::Grapheme(g) if !Self::is_whitespace(g)))
+                    .map_or(0, |i| i + 1);
+                let whitespace = content.split_off(word_end);
+
+                let word = Self {
+                    content,
+                    line_index,
+                    offset,
+                };
+
+                let mut parts = word.into_wrapped(max_width as u16);
+                let mut last = parts.pop().expect("Wrapped word has parts");
+                lines.extend(parts);
+
+                last.content.extend(whitespace);
+                line_width = last.width();
+                line = last;
+            } else {
+                line.content.extend(segment);
+                line_width += width;
+            }
+
+            offset += graphemes;
+        }
+
+        lines.push(line);
+        lines
+    }
+
+    /// Returns whether the line can be broken before each of its graphemes, following the
+    /// Unicode line breaking algorithm
+    fn break_opportunities(&self) -> Vec<bool> {
+        let mut text = String::new();
+        let mut starts = Vec::new();
+        for unit in &self.content {
+            if let StyledUnit::Grapheme(grapheme) = unit {
+                starts.push(text.len());
+                text.push_str(&grapheme.str);
+            }
+        }
+
+        // Breaks are given as byte indices after which the line can end
+        let mut breaks = unicode_linebreak::linebreaks(&text)
+            .map(|(index, _)| index)
+            .peekable();
+        starts
+            .into_iter()
+            .map(|start| {
+                while breaks.next_if(|index| *index < start).is_some() {}
+                breaks.next_if_eq(&start).is_some()
+            })
+            .collect()
+    }
+
+    /// Returns true if the grapheme is a breakable whitespace
+    fn is_whitespace(grapheme: &VisualGrapheme) -> bool {
+        let is_no_break = |c: char| matches!(c, '\u{00A0}' | '\u{2007}' | '\u{202F}');
+
+        grapheme.str == "\u{200B}"
+            || grapheme
+                .str
+                .chars()
+                .all(|c| c.is_whitespace() && !is_no_break(c))
+    }
+
     /// Returns the last grapheme index in the line, if any
     fn last_grapheme_index(&self) -> Option<usize> {
         self.content.iter().rev().find_map(|unit| {
@@ -254,3 +388,93 @@ impl VisualLine {
         last_index + 1
     }
 }
+
+#[cfg(test)]
+mod wrap_tests {
+    use super::*;
+
+    fn line(str: &str) -> VisualLine {
+        VisualLine::from_buffer_line(&BufferLine::new(str.to_string()), 0)
+    }
+
+    fn strings(lines: &[VisualLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| {
+                l.content
+                    .iter()
+                    .filter_map(|u| match u {
+                        StyledUnit::Grapheme(g) => Some(g.str.as_str()),
+                        StyledUnit::Code(_) => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn offsets(lines: &[VisualLine]) -> Vec<usize> {
+        lines.iter().map(|l| l.offset).collect()
+    }
+
+    #[test]
+    fn grapheme_wrap_offsets() {
+        let lines = line("abcdefg").into_wrapped(3);
+        assert_eq!(strings(&lines), ["abc", "def", "g"]);
+        assert_eq!(offsets(&lines), [0, 3, 6]);
+    }
+
+    #[test]
+    fn word_wrap_at_whitespace() {
+        let lines = line("hello world, wrap me").into_word_wrapped(11);
+        assert_eq!(strings(&lines), ["hello ", "world, wrap ", "me"]);
+        assert_eq!(offsets(&lines), [0, 6, 18]);
+    }
+
+    #[test]
+    fn word_wrap_exact_fit() {
+        let lines = line("hello world").into_word_wrapped(5);
+        assert_eq!(strings(&lines), ["hello ", "world"]);
+    }
+
+    #[test]
+    fn word_wrap_long_word_fallback() {
+        let lines = line("a abcdefgh b").into_word_wrapped(4);
+        assert_eq!(strings(&lines), ["a ", "abcd", "efgh ", "b"]);
+        assert_eq!(offsets(&lines), [0, 2, 6, 11]);
+    }
+
+    #[test]
+    fn word_wrap_hyphen_and_wide() {
+        let lines = line("well-known").into_word_wrapped(7);
+        assert_eq!(strings(&lines), ["well-", "known"]);
+
+        let lines = line("漢字漢字").into_word_wrapped(5);
+        assert_eq!(strings(&lines), ["漢字", "漢字"]);
+    }
+
+    #[test]
+    fn word_wrap_no_break_space() {
+        let lines = line("a\u{00A0}b c").into_word_wrapped(3);
+        assert_eq!(strings(&lines), ["a\u{00A0}b ", "c"]);
+    }
+
+    #[test]
+    fn word_wrap_punctuation() {
+        // Closing punctuation can't start a line, opening punctuation can't end one
+        let lines = line("漢字漢字。").into_word_wrapped(8);
+        assert_eq!(strings(&lines), ["漢字漢", "字。"]);
+
+        let lines = line("a (b)").into_word_wrapped(3);
+        assert_eq!(strings(&lines), ["a ", "(b)"]);
+    }
+
+    #[test]
+    fn word_wrap_keeps_styles() {
+        let mut visual = line("ab cd");
+        visual.add_style(Code::Foreground(crossterm::style::Color::Red), 1, 3);
+
+        let lines = visual.into_word_wrapped(3);
+        assert_eq!(strings(&lines), ["ab ", "cd"]);
+        assert!(lines[1].content[1].is_code());
+    }
+}